@@ -1,5 +1,5 @@
 use darling::{ast, FromDeriveInput, FromField};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::parse_macro_input;
 
@@ -9,11 +9,78 @@ struct FieldOpts {
     ident: Option<syn::Ident>,
     // ty: syn::Type,
 
-    #[darling(default)]
     relation: Option<String>,
 
-    #[darling(default)]
+    aggregate_of: Option<String>,
+
     pk: Option<String>,
+
+    scalar: Option<String>,
+}
+
+fn scalar(ty: &str) -> &str {
+    match ty {
+        "String" | "str" | "char" => "String",
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => "Int",
+        "f32" | "f64" => "Float",
+        "bool" => "Boolean",
+        other => other,
+    }
+}
+
+fn pascal(name: &str) -> String {
+    name.split('_')
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars.next().into_iter().flat_map(char::to_uppercase).chain(chars)
+        })
+        .collect()
+}
+
+// GraphQL names are plain ASCII (`[_A-Za-z][_0-9A-Za-z]*`), unlike Rust identifiers.
+fn graphql_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+// Attribute values end up as identifiers, so reject anything that isn't one instead of panicking.
+fn ident(name: &str, span: Span) -> syn::Result<syn::Ident> {
+    let error = || syn::Error::new(span, format!("`{name}` is not a valid identifier"));
+    if !graphql_name(name) {
+        return Err(error());
+    }
+    let mut ident = syn::parse_str::<syn::Ident>(name).map_err(|_| error())?;
+    ident.set_span(span);
+    Ok(ident)
+}
+
+// A unit enum whose variants render as the given GraphQL enum values.
+fn enumeration(ident: &syn::Ident, values: &[String]) -> syn::Result<TokenStream> {
+    let variants = values
+        .iter()
+        .map(|value| {
+            let error = || syn::Error::new(ident.span(), format!("`{value}` can't be turned into a variant of {ident}"));
+            if !graphql_name(value) || matches!(value.as_str(), "true" | "false" | "null") {
+                return Err(error());
+            }
+            self::ident(&pascal(value), ident.span()).map_err(|_| error())
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+        pub enum #ident {
+            #(#[serde(rename = #values)] #variants),*
+        }
+        impl std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match *self {
+                    #(Self::#variants => write!(f, #values)),*
+                }
+            }
+        }
+    })
 }
 
 #[derive(FromDeriveInput)]
@@ -22,72 +89,150 @@ struct TraitOpts {
     ident: syn::Ident,
     data: ast::Data<(), FieldOpts>,
     table: String,
+
+    #[darling(multiple, rename = "constraint")]
+    constraints: Vec<String>,
 }
 
 impl ToTokens for TraitOpts {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self.expand() {
+            Ok(stream) => tokens.extend(stream),
+            Err(error) => tokens.extend(error.to_compile_error()),
+        }
+    }
+}
+
+impl TraitOpts {
+    fn expand(&self) -> syn::Result<TokenStream> {
         let fields = match &self.data {
             ast::Data::Struct(fields) => &fields.fields,
             _ => unimplemented!(),
         };
 
-        let pks: Vec<_> = fields
-            .iter()
-            .filter_map(|f| Some((&f.ident, syn::Ident::new(f.pk.as_ref()?, self.ident.span()))))
-            .collect();
+        let span = self.ident.span();
 
+        let pks = fields
+            .iter()
+            .filter_map(|f| Some((&f.ident, f.pk.as_ref()?)))
+            .map(|(field, pk)| Ok((field, ident(pk, span)?)))
+            .collect::<syn::Result<Vec<_>>>()?;
 
         let pk_fields = pks.iter().map(|(ident, pk)| quote!(pub #ident: #pk));
         let pk_params = pks.iter().map(|(ident, pk)| quote!(#ident: impl std::convert::Into<#pk>));
         let pk_intos = pks.iter().map(|(ident, _)| quote!(#ident: #ident.into()));
 
+        let pk_types = fields.iter().filter_map(|f| {
+            let ident = f.ident.as_ref()?.to_string();
+            let ty = format!("{}!", f.scalar.as_deref().unwrap_or(scalar(f.pk.as_ref()?)));
+            Some(quote!((#ident, #ty)))
+        });
+
         let pk_ident = syn::Ident::new(&format!("{}Pk", self.ident), self.ident.span());
 
+        let columns: Vec<_> = fields
+            .iter()
+            .filter(|f| f.relation.is_none() && f.aggregate_of.is_none())
+            .filter_map(|f| Some(f.ident.as_ref()?.to_string()))
+            .collect();
+
+        let column_ident = syn::Ident::new(&format!("{}Column", self.ident), self.ident.span());
+        let constraint_ident = syn::Ident::new(&format!("{}Constraint", self.ident), self.ident.span());
+
+        let column_enum = enumeration(&column_ident, &columns)?;
+        let constraint_enum = enumeration(&constraint_ident, &self.constraints)?;
+
+        // The related type of the relation field an aggregate field points at.
+        let aggregated = |of: &str| {
+            let relation = fields
+                .iter()
+                .find(|f| f.ident.as_ref().is_some_and(|ident| ident == of))
+                .and_then(|f| f.relation.as_ref());
+
+            match relation {
+                Some(ty) => ident(ty, span),
+                None => Err(syn::Error::new(span, format!("aggregate_of = \"{of}\" must name a relation field"))),
+            }
+        };
+
         let field_extractor = |field: &FieldOpts| {
             let ident = &field.ident;
 
-            match field.relation {
-                Some(_) => quote! {
-                    pub fn #ident<'a, S: hasura::Hasura>(inner: hasura::Fields<'a, S>) -> hasura::Field<'a, Self> {
-                        hasura::Field::recursive(stringify!(#ident), inner)
+            if let Some(of) = &field.aggregate_of {
+                let ty = aggregated(of)?;
+                let name = format!("{of}_aggregate");
+
+                // the response has to come back under the struct field's name to decode
+                let field = quote!(hasura::Field::aggregate(#name, aggregate));
+                let field = match ident.as_ref().is_some_and(|ident| *ident != name) {
+                    true => quote!(hasura::Field { alias: Some(stringify!(#ident)), ..#field }),
+                    false => field,
+                };
+
+                return Ok(quote! {
+                    pub fn #ident<'a>(aggregate: impl Into<hasura::NestedAggregate<'a, #ty>>) -> hasura::Field<'a, Self> {
+                        #field
                     }
-                },
+                });
+            }
+
+            Ok(match &field.relation {
+                Some(ty) => {
+                    let ty = self::ident(ty, span)?;
+                    quote! {
+                        pub fn #ident<'a>(inner: impl Into<hasura::Nested<'a, #ty>>) -> hasura::Field<'a, Self> {
+                            hasura::Field::recursive(stringify!(#ident), inner)
+                        }
+                    }
+                }
                 None => quote! {
                     pub fn #ident<'a>() -> hasura::Field<'a, Self> {
                         hasura::Field::new(stringify!(#ident))
                     }
                 },
-            }
+            })
         };
 
         let field_recursive = |field: &FieldOpts| {
             let ident = &field.ident;
 
-            match &field.relation {
+            if field.aggregate_of.is_some() {
+                return Ok(quote! { Self::#ident(hasura::AggregateFields::default()) });
+            }
+
+            Ok(match &field.relation {
                 Some(ty) => {
-                    let ty = syn::Ident::new(&ty, self.ident.span());
+                    let ty = self::ident(ty, span)?;
                     quote! { Self::#ident(#ty::all()) }
                 }
                 None => quote! { Self::#ident() },
-            }
+            })
         };
 
-        let field_extractors = fields.iter().map(field_extractor);
-        let field_recursives: Vec<_> = fields.iter().map(field_recursive).collect();
+        let field_extractors = fields.iter().map(field_extractor).collect::<syn::Result<Vec<_>>>()?;
+        let field_recursives = fields.iter().map(field_recursive).collect::<syn::Result<Vec<_>>>()?;
 
         let Self { ident, table, .. } = self;
 
-        let stream = quote! {
+        Ok(quote! {
             #[derive(serde::Serialize)]
             pub struct #pk_ident {
                 #(#pk_fields),*
             }
+            #column_enum
+            #constraint_enum
+
             impl hasura::Hasura for #ident {
                 type Pk = #pk_ident;
+                type Column = #column_ident;
+                type Constraint = #constraint_ident;
 
                 fn table<'a>() -> &'a str {
                     #table
                 }
+                fn pk_types<'a>() -> Vec<(&'a str, &'a str)> {
+                    vec![#(#pk_types),*]
+                }
                 fn all<'a>() -> hasura::Fields<'a, Self> {
                     hasura::Fields{inner: vec![#(#field_recursives),*]}
                 }
@@ -107,13 +252,14 @@ impl ToTokens for TraitOpts {
                     }
                 }
             }
-        };
-        tokens.extend(stream);
+        })
     }
 }
 
 #[proc_macro_derive(Hasura, attributes(hasura))]
 pub fn derive_hasura(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let opts = TraitOpts::from_derive_input(&parse_macro_input!(input)).unwrap();
-    opts.to_token_stream().into()
+    match TraitOpts::from_derive_input(&parse_macro_input!(input)) {
+        Ok(opts) => opts.to_token_stream().into(),
+        Err(error) => error.write_errors().into(),
+    }
 }
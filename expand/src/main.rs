@@ -3,20 +3,20 @@ use hasura::Hasura;
 #[derive(Hasura)]
 #[hasura(table = "managers")]
 pub struct Manager {
-    #[hasura(pk)]
-    name: String,
+    #[hasura(pk = "String")]
+    pub name: String,
 
-    weight: f64,
+    pub weight: f64,
 }
 
 #[derive(Hasura)]
 #[hasura(table = "stores")]
 pub struct Store {
-    #[hasura(pk)]
-    id: Option<u64>,
+    #[hasura(pk = "u64")]
+    pub id: Option<u64>,
 
     #[hasura(relation = "Manager")]
-    manager: Option<Manager>,
+    pub manager: Option<Manager>,
 }
 
 fn main() {
@@ -0,0 +1,62 @@
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use crate::{Field, Hasura};
+
+#[derive(Clone)]
+pub struct AggregateFields<'a, T: Hasura> {
+    pub count: bool,
+    pub sum: Vec<Field<'a, T>>,
+    pub avg: Vec<Field<'a, T>>,
+    pub max: Vec<Field<'a, T>>,
+    pub min: Vec<Field<'a, T>>,
+}
+
+impl<'a, T: Hasura> Default for AggregateFields<'a, T> {
+    fn default() -> Self {
+        Self {
+            count: true,
+            sum: vec![],
+            avg: vec![],
+            max: vec![],
+            min: vec![],
+        }
+    }
+}
+
+impl<'a, T: Hasura> AggregateFields<'a, T> {
+    pub fn is_empty(&self) -> bool {
+        !self.count && [&self.sum, &self.avg, &self.max, &self.min].iter().all(|x| x.is_empty())
+    }
+
+    // a selection set can't be empty, so fall back on counting when nothing else is selected
+    pub(crate) fn selection(&self, nodes: Option<String>) -> String {
+        match (self.is_empty(), nodes) {
+            (true, None) => "aggregate { count }".to_string(),
+            (true, Some(nodes)) => format!("nodes {{ {nodes} }}"),
+            (false, None) => self.to_string(),
+            (false, Some(nodes)) => format!("{self} nodes {{ {nodes} }}"),
+        }
+    }
+}
+
+impl<'a, T: Hasura> Display for AggregateFields<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let functions = [("sum", &self.sum), ("avg", &self.avg), ("max", &self.max), ("min", &self.min)];
+
+        let mut inner: Vec<_> = functions
+            .iter()
+            .filter(|(_, fields)| !fields.is_empty())
+            .map(|(name, fields)| format!("{name} {{ {} }}", fields.iter().map(|field| field.name).join(" ")))
+            .collect();
+
+        if self.count {
+            inner.insert(0, "count".to_string());
+        }
+
+        write!(f, "aggregate {{ {} }}", inner.join(" "))
+    }
+}
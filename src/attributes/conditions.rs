@@ -1,37 +1,159 @@
-use crate::{Field, Hasura};
-use std::fmt::{Display, Formatter};
-use itertools::Itertools;
+use crate::{Braced, Context, Count, Field, Fields, Hasura, Render, Result, Variable};
+use serde_json::{json, Map, Value};
 
-pub trait Condition: Display {}
+pub trait Condition: Render {
+    fn operator(&self) -> &'static str;
+    fn value(&self) -> Result<Value>;
+}
 
 #[macro_export]
 macro_rules! impl_cond {
-    ($t:ident, $n:tt ;$($i:ty),*) => {
+    ($t:ident, $n:tt) => {
+        $crate::impl_cond!(@struct $t, $n);
+
+        impl<T: $crate::ScalarValue> $crate::Condition for $t<T> {
+            $crate::impl_cond!(@body $n);
+        }
+    };
+    ($t:ident, $n:tt; _) => {
+        $crate::impl_cond!(@struct $t, $n);
+
+        impl<T: serde::Serialize> $crate::Condition for $t<T> {
+            $crate::impl_cond!(@body $n);
+        }
+    };
+    ($t:ident, $n:tt; [T]) => {
+        $crate::impl_cond!(@struct $t, $n);
+
+        impl<T: $crate::ScalarValue> $crate::Condition for $t<Vec<T>> {
+            $crate::impl_cond!(@body $n);
+        }
+    };
+    ($t:ident, $n:tt; $($i:ty),*) => {
+        $crate::impl_cond!(@struct $t, $n);
+
+        $(impl $crate::Condition for $t<$i> {
+            $crate::impl_cond!(@body $n);
+        })*
+    };
+    (@struct $t:ident, $n:tt) => {
         pub struct $t<T: serde::Serialize>(pub T);
 
-        impl<T: serde::Serialize> std::fmt::Display for $t<T> {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                write!(f, stringify!($n:{}), crate::to_string(&self.0, true).unwrap())
+        impl<T: serde::Serialize> $crate::Render for $t<T> {
+            fn render(&self, _: &mut $crate::Context) -> $crate::Result<String> {
+                Ok(format!(concat!(stringify!($n), ": {}"), $crate::to_string(&self.0, true)?))
             }
         }
-
-        $(impl Condition for $t<$i> {})*
+    };
+    (@body $n:tt) => {
+        fn operator(&self) -> &'static str {
+            stringify!($n)
+        }
+        fn value(&self) -> $crate::Result<serde_json::Value> {
+            Ok(serde_json::to_value(&self.0)?)
+        }
     };
 }
 
-impl_cond!(Eq,_eq;bool,u64,i64,f64,String,&'_ str);
-impl_cond!(Gt,_gt;u64,i64,f64,String,&'_ str);
-impl_cond!(Gte,_gte;u64,i64,f64,String,&'_ str);
-impl_cond!(Neq,_neq;u64,i64,f64,String,&'_ str);
-impl_cond!(Lt,_lt;u64,i64,f64,String,&'_ str);
+impl_cond!(Eq,_eq);
+impl_cond!(Gt,_gt);
+impl_cond!(Gte,_gte);
+impl_cond!(Neq,_neq);
+impl_cond!(Lt,_lt);
+impl_cond!(Lte,_lte);
+impl_cond!(In,_in;[T]);
+impl_cond!(Nin,_nin;[T]);
+impl_cond!(IsNull,_is_null;bool);
 impl_cond!(Ilike,_ilike;String,&'_ str);
 impl_cond!(Like,_like;String,&'_ str);
+impl_cond!(Nilike,_nilike;String,&'_ str);
+impl_cond!(Nlike,_nlike;String,&'_ str);
+impl_cond!(Similar,_similar;String,&'_ str);
+impl_cond!(Nsimilar,_nsimilar;String,&'_ str);
+impl_cond!(Regex,_regex;String,&'_ str);
+impl_cond!(Iregex,_iregex;String,&'_ str);
+impl_cond!(Nregex,_nregex;String,&'_ str);
+impl_cond!(Niregex,_niregex;String,&'_ str);
+impl_cond!(Contains,_contains;_);
+impl_cond!(ContainedIn,_contained_in;_);
+impl_cond!(HasKey,_has_key;String,&'_ str);
+impl_cond!(HasKeysAny,_has_keys_any;Vec<String>,Vec<&'_ str>);
+impl_cond!(HasKeysAll,_has_keys_all;Vec<String>,Vec<&'_ str>);
+impl_cond!(Ancestor,_ancestor;String,&'_ str);
+impl_cond!(AncestorAny,_ancestor_any;Vec<String>,Vec<&'_ str>);
+impl_cond!(Descendant,_descendant;String,&'_ str);
+impl_cond!(DescendantAny,_descendant_any;Vec<String>,Vec<&'_ str>);
+impl_cond!(Matches,_matches;String,&'_ str);
+impl_cond!(MatchesAny,_matches_any;Vec<String>,Vec<&'_ str>);
+impl_cond!(MatchesFulltext,_matches_fulltext;String,&'_ str);
+
+macro_rules! impl_column_cond {
+    ($t:ident, $n:tt) => {
+        pub struct $t<'a, T: Hasura>(pub Field<'a, T>);
+
+        impl<'a, T: Hasura> Render for $t<'a, T> {
+            fn render(&self, _: &mut Context) -> Result<String> {
+                Ok(format!(concat!(stringify!($n), ": [\"{}\"]"), self.0.name))
+            }
+        }
+
+        impl<'a, T: Hasura> Condition for $t<'a, T> {
+            fn operator(&self) -> &'static str {
+                stringify!($n)
+            }
+            fn value(&self) -> Result<Value> {
+                Ok(json!([self.0.name]))
+            }
+        }
+    };
+}
+
+impl_column_cond!(Ceq, _ceq);
+impl_column_cond!(Cne, _cne);
+impl_column_cond!(Cgt, _cgt);
+impl_column_cond!(Cgte, _cgte);
+impl_column_cond!(Clt, _clt);
+impl_column_cond!(Clte, _clte);
+
+/// Conditions on a jsonb column cast to `String`.
+pub struct Cast(pub Vec<Box<dyn Condition>>);
+
+impl Condition for Cast {
+    fn operator(&self) -> &'static str {
+        "_cast"
+    }
+    fn value(&self) -> Result<Value> {
+        Ok(json!({ "String": operators(&self.0)? }))
+    }
+}
+
+impl Render for Cast {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        Ok(format!("_cast: {{ String: {{ {} }} }}", joined(&self.0, context)?))
+    }
+}
+
+fn operators(conditions: &[Box<dyn Condition + '_>]) -> Result<Map<String, Value>> {
+    conditions.iter().map(|c| Ok((c.operator().to_string(), c.value()?))).collect()
+}
+
+fn joined(conditions: &[Box<dyn Condition + '_>], context: &mut Context) -> Result<String> {
+    let conditions = conditions.iter().map(|c| c.render(context)).collect::<Result<Vec<_>>>()?;
+    Ok(conditions.join(", "))
+}
+
+/// A boolean expression on any table, used to nest conditions across relations.
+pub trait Expression: Render {
+    fn value(&self) -> Result<Value>;
+}
 
 pub enum Conditions<'a, T: Hasura> {
     And(Box<Conditions<'a, T>>, Box<Conditions<'a, T>>),
     Or(Box<Conditions<'a, T>>, Box<Conditions<'a, T>>),
     Not(Box<Conditions<'a, T>>),
-    Field(Field<'a, T>, Vec<Box<dyn Condition>>),
+    Field(Field<'a, T>, Vec<Box<dyn Condition + 'a>>),
+    Relation(Field<'a, T>, Box<dyn Expression + 'a>),
+    Aggregate(Field<'a, T>, Box<dyn Expression + 'a>),
     None
 }
 
@@ -44,26 +166,74 @@ impl<'a, T: Hasura> Conditions<'a, T> {
         Self::Or(Box::new(self), Box::new(other))
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn not(self) -> Self {
         Self::Not(Box::new(self))
     }
 
-    pub fn single(field: Field<'a, T>, condition: impl Condition + 'static) -> Self {
+    pub fn single(field: Field<'a, T>, condition: impl Condition + 'a) -> Self {
         Self::Field(field, vec![Box::new(condition)])
     }
-    pub fn many(field: Field<'a, T>, conditions: Vec<Box<dyn Condition>>) -> Self {
+    pub fn many(field: Field<'a, T>, conditions: Vec<Box<dyn Condition + 'a>>) -> Self {
         Self::Field(field, conditions)
     }
-}
+    pub fn relation<S: Hasura + 'a>(
+        relation: impl FnOnce(Fields<'a, S>) -> Field<'a, T>,
+        conditions: Conditions<'a, S>,
+    ) -> Self {
+        let field = relation(Fields { inner: vec![] });
+        Self::Relation(field, Box::new(conditions))
+    }
+    pub fn aggregate<S: Hasura + 'a>(relation: impl FnOnce(Fields<'a, S>) -> Field<'a, T>, count: Count<'a, S>) -> Self {
+        let field = relation(Fields { inner: vec![] });
+        Self::Aggregate(field, Box::new(count))
+    }
 
-impl<'a, T: Hasura> Display for Conditions<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    /// Whether the expression matches every row, like `{}` or any combination that reduces to it.
+    pub fn is_unconditional(&self) -> bool {
         match self {
-            Self::And(l, r) => write!(f, "_and{{ {}, {} }}", l, r),
-            Self::Or(l, r) => write!(f, "_or{{ {}, {} }}", l, r),
-            Self::Not(c) => write!(f, "_not{{ {} }}", c),
-            Self::Field(field, cond) => write!(f, "{}: {{ {} }}", field, cond.iter().join(", ")),
-            Self::None => write!(f, "")
+            Self::And(l, r) => l.is_unconditional() && r.is_unconditional(),
+            Self::Or(l, r) => l.is_unconditional() || r.is_unconditional(),
+            Self::Not(c) => c.is_unconditional(),
+            Self::Field(_, cond) => cond.is_empty(),
+            Self::Relation(..) | Self::Aggregate(..) => false,
+            Self::None => true,
         }
     }
+
+    pub fn value(&self) -> Result<Value> {
+        Ok(match self {
+            Self::And(l, r) => json!({"_and": [l.value()?, r.value()?]}),
+            Self::Or(l, r) => json!({"_or": [l.value()?, r.value()?]}),
+            Self::Not(c) => json!({"_not": c.value()?}),
+            Self::Field(field, cond) => json!({ field.name: operators(cond)? }),
+            Self::Relation(field, conditions) => json!({ field.name: conditions.value()? }),
+            Self::Aggregate(field, predicate) => json!({ format!("{}_aggregate", field.name): predicate.value()? }),
+            Self::None => json!({}),
+        })
+    }
+
+    pub(crate) fn variable(&self) -> Variable<'_> {
+        Variable::new(format!("{}_bool_exp!", T::table()), Braced(self), || self.value())
+    }
+}
+
+impl<'a, T: Hasura> Expression for Conditions<'a, T> {
+    fn value(&self) -> Result<Value> {
+        Conditions::value(self)
+    }
+}
+
+impl<'a, T: Hasura> Render for Conditions<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        Ok(match self {
+            Self::And(l, r) => format!("_and: [{}, {}]", Braced(l).render(context)?, Braced(r).render(context)?),
+            Self::Or(l, r) => format!("_or: [{}, {}]", Braced(l).render(context)?, Braced(r).render(context)?),
+            Self::Not(c) => format!("_not: {}", Braced(c).render(context)?),
+            Self::Field(field, cond) => format!("{}: {{ {} }}", field.name, joined(cond, context)?),
+            Self::Relation(field, conditions) => format!("{}: {}", field.name, Braced(conditions).render(context)?),
+            Self::Aggregate(field, predicate) => format!("{}_aggregate: {}", field.name, Braced(predicate).render(context)?),
+            Self::None => String::new(),
+        })
+    }
 }
@@ -0,0 +1,56 @@
+use std::fmt::{Display, Formatter};
+use serde_json::{json, Value};
+use crate::{deferred, Context, Field, Hasura, Render, Result, Serialized, Variable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorOrdering {
+    Asc,
+    Desc,
+}
+
+impl Display for CursorOrdering {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CursorOrdering::Asc => write!(f, "ASC"),
+            CursorOrdering::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Cursor<'a, T: Hasura> {
+    pub field: Field<'a, T>,
+    pub initial_value: Value,
+    pub ordering: CursorOrdering,
+    error: Option<String>,
+}
+
+impl<'a, T: Hasura> Cursor<'a, T> {
+    pub fn new(field: Field<'a, T>, initial_value: impl serde::Serialize) -> Self {
+        let (initial_value, error) = match serde_json::to_value(initial_value) {
+            Ok(value) => (value, None),
+            Err(error) => (Value::Null, Some(error.to_string())),
+        };
+
+        Self {
+            field,
+            initial_value,
+            ordering: CursorOrdering::Asc,
+            error,
+        }
+    }
+    pub fn ordering(mut self, ordering: CursorOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+}
+
+impl<'a, T: Hasura> Render for Cursor<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        deferred(&self.error)?;
+
+        let value = json!({ self.field.name: self.initial_value });
+        let initial_value = Variable::new(format!("{}_stream_cursor_value_input!", T::table()), Serialized(&value), || Ok(value.clone()));
+        Ok(format!("{{initial_value: {}, ordering: {}}}", initial_value.render(context)?, self.ordering))
+    }
+}
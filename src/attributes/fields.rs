@@ -1,18 +1,22 @@
-use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
-use itertools::Itertools;
+use std::rc::Rc;
 use crate::traits::Hasura;
+use crate::{Context, Nested, NestedAggregate, Render, Result};
 
 #[derive(Clone)]
 pub struct Field<'a, T: Hasura + ?Sized> {
     pub name: &'a str,
-    pub inner: Vec<String>,
+    /// The response key when it differs from `name`. Results always decode into `T`, so this has to be the
+    /// struct field the value belongs to; there is no way to alias arbitrary fields of a selection.
+    pub alias: Option<&'a str>,
+    /// The arguments and selection of a relation, rendered together with the rest of the operation.
+    pub nested: Option<Rc<dyn Render + 'a>>,
     pub phantom: PhantomData<T>,
 }
 
 impl<'a, T: Hasura> PartialEq for Field<'a, T> {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(other.name)
+        self.name.eq(other.name) && self.alias.eq(&other.alias)
     }
 }
 
@@ -20,26 +24,39 @@ impl<'a, T: Hasura> Field<'a, T> {
     pub fn new(name: &'a str) -> Self {
         Field {
             name,
-            inner: vec![],
-            phantom: PhantomData::default(),
+            alias: None,
+            nested: None,
+            phantom: PhantomData,
         }
     }
-    pub fn recursive<S: Hasura>(name: &'a str, keys: Fields<'a, S>) -> Self {
-        let inner = keys.inner.into_iter().map(|k| k.to_string()).collect();
+    pub fn recursive<S: Hasura + 'a>(name: &'a str, nested: impl Into<Nested<'a, S>>) -> Self {
         Field {
             name,
-            inner,
-            phantom: PhantomData::default(),
+            alias: None,
+            nested: Some(Rc::new(nested.into())),
+            phantom: PhantomData,
+        }
+    }
+    pub fn aggregate<S: Hasura + 'a>(name: &'a str, aggregate: impl Into<NestedAggregate<'a, S>>) -> Self {
+        Field {
+            name,
+            alias: None,
+            nested: Some(Rc::new(aggregate.into())),
+            phantom: PhantomData,
         }
     }
 }
 
-impl<'a, T: Hasura> Display for Field<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self.inner.is_empty() {
-            true => write!(f, "{}", self.name),
-            false => write!(f, "{} {{ {} }}", self.name, self.inner.iter().join(" ")),
-        }
+impl<'a, T: Hasura> Render for Field<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let nested = match &self.nested {
+            Some(nested) => nested.render(context)?,
+            None => String::new(),
+        };
+        Ok(match self.alias {
+            Some(alias) => format!("{alias}: {}{nested}", self.name),
+            None => format!("{}{nested}", self.name),
+        })
     }
 }
 
@@ -48,9 +65,16 @@ pub struct Fields<'a, T: Hasura + Sized> {
     pub inner: Vec<Field<'a, T>>,
 }
 
-impl<'a, T: Hasura> Display for Fields<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.inner.iter().join(" "))
+impl<'a, T: Hasura> Fields<'a, T> {
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<'a, T: Hasura> Render for Fields<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let fields = self.inner.iter().map(|field| field.render(context)).collect::<Result<Vec<_>>>()?;
+        Ok(fields.join(" "))
     }
 }
 
@@ -1,9 +1,29 @@
+mod aggregate;
 mod conditions;
+#[cfg(feature = "subscriptions")]
+mod cursor;
 mod fields;
+mod nested;
 mod on_conflict;
+mod operators;
 mod order_by;
+#[cfg(feature = "postgis")]
+mod postgis;
+mod predicates;
+mod scalar;
+mod set;
 
+pub use aggregate::*;
 pub use conditions::*;
+#[cfg(feature = "subscriptions")]
+pub use cursor::*;
 pub use fields::*;
+pub use nested::*;
 pub use on_conflict::*;
+pub use operators::*;
 pub use order_by::*;
+#[cfg(feature = "postgis")]
+pub use postgis::*;
+pub use predicates::*;
+pub use scalar::*;
+pub use set::*;
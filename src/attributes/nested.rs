@@ -0,0 +1,145 @@
+use crate::{AggregateFields, Conditions, Context, Fields, Hasura, Listed, OrderBy, Render, Result};
+
+/// The selection of a relation, with the arguments Hasura accepts on it.
+pub struct Nested<'a, T: Hasura> {
+    pub distinct_on: Option<T::Column>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub conditions: Option<Conditions<'a, T>>,
+    pub order_by: Vec<OrderBy<'a, T>>,
+    pub returning: Fields<'a, T>,
+}
+
+impl<'a, T: Hasura> Nested<'a, T> {
+    pub fn new(returning: Fields<'a, T>) -> Self {
+        Self {
+            distinct_on: None,
+            limit: None,
+            offset: None,
+            conditions: None,
+            order_by: vec![],
+            returning,
+        }
+    }
+
+    pub fn distinct_on(mut self, distinct_on: T::Column) -> Self {
+        self.distinct_on = Some(distinct_on);
+        self
+    }
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+    pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+    pub fn order_by(mut self, order_by: Vec<OrderBy<'a, T>>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    fn args(&self, context: &mut Context) -> Result<String> {
+        let mut args = vec![];
+        if !self.order_by.is_empty() {
+            args.push(format!("order_by: {}", Listed(&self.order_by)));
+        }
+        if let Some(distinct_on) = &self.distinct_on {
+            args.push(format!("distinct_on: {distinct_on}"));
+        }
+        if let Some(limit) = self.limit {
+            args.push(format!("limit: {limit}"));
+        }
+        if let Some(offset) = self.offset {
+            args.push(format!("offset: {offset}"));
+        }
+        if let Some(conditions) = &self.conditions {
+            args.push(format!("where: {}", conditions.variable().render(context)?));
+        }
+
+        Ok(match args.is_empty() {
+            true => String::new(),
+            false => format!("({})", args.join(", ")),
+        })
+    }
+}
+
+impl<'a, T: Hasura> Render for Nested<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let args = self.args(context)?;
+        Ok(match self.returning.is_empty() {
+            true => args,
+            false => format!("{args} {{ {} }}", self.returning.render(context)?),
+        })
+    }
+}
+
+/// The selection of a `<relation>_aggregate` field, with the same arguments as `Nested`.
+pub struct NestedAggregate<'a, T: Hasura> {
+    pub nested: Nested<'a, T>,
+    pub aggregate: AggregateFields<'a, T>,
+    pub nodes: bool,
+}
+
+impl<'a, T: Hasura> NestedAggregate<'a, T> {
+    pub fn new(aggregate: AggregateFields<'a, T>) -> Self {
+        Self {
+            nested: Nested::new(Fields { inner: vec![] }),
+            aggregate,
+            nodes: false,
+        }
+    }
+
+    pub fn distinct_on(mut self, distinct_on: T::Column) -> Self {
+        self.nested = self.nested.distinct_on(distinct_on);
+        self
+    }
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.nested = self.nested.limit(limit);
+        self
+    }
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.nested = self.nested.offset(offset);
+        self
+    }
+    pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
+        self.nested = self.nested.conditions(conditions);
+        self
+    }
+    pub fn order_by(mut self, order_by: Vec<OrderBy<'a, T>>) -> Self {
+        self.nested = self.nested.order_by(order_by);
+        self
+    }
+    pub fn nodes(mut self, nodes: Fields<'a, T>) -> Self {
+        self.nested.returning = nodes;
+        self.nodes = true;
+        self
+    }
+}
+
+impl<'a, T: Hasura> Render for NestedAggregate<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let args = self.nested.args(context)?;
+        let nodes = match self.nodes {
+            true => Some(self.nested.returning.render(context)?),
+            false => None,
+        };
+        Ok(format!("{args} {{ {} }}", self.aggregate.selection(nodes)))
+    }
+}
+
+impl<'a, T: Hasura> From<AggregateFields<'a, T>> for NestedAggregate<'a, T> {
+    fn from(aggregate: AggregateFields<'a, T>) -> Self {
+        Self::new(aggregate)
+    }
+}
+
+impl<'a, T: Hasura> From<Fields<'a, T>> for Nested<'a, T> {
+    fn from(returning: Fields<'a, T>) -> Self {
+        Self::new(returning)
+    }
+}
@@ -1,11 +1,38 @@
-use std::fmt::Display;
+use itertools::Itertools;
+use crate::{Conditions, Context, Hasura, Render, Result};
 
-pub struct OnConflict {
-    // TODO: implement
+pub struct OnConflict<'a, T: Hasura> {
+    pub constraint: T::Constraint,
+    pub update_columns: Vec<T::Column>,
+    pub conditions: Option<Conditions<'a, T>>,
 }
 
-impl Display for OnConflict {
-    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+impl<'a, T: Hasura> OnConflict<'a, T> {
+    pub fn new(constraint: T::Constraint) -> Self {
+        Self {
+            constraint,
+            update_columns: vec![],
+            conditions: None,
+        }
+    }
+    pub fn update_columns(mut self, update_columns: Vec<T::Column>) -> Self {
+        self.update_columns = update_columns;
+        self
+    }
+    pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+}
+
+impl<'a, T: Hasura> Render for OnConflict<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let columns = self.update_columns.iter().join(", ");
+        let mut output = format!("{{constraint: {}, update_columns: [{columns}]", self.constraint);
+
+        if let Some(conditions) = &self.conditions {
+            output += &format!(", where: {}", conditions.variable().render(context)?);
+        }
+        Ok(output + "}")
     }
 }
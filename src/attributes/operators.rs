@@ -0,0 +1,120 @@
+use serde_json::{Map, Value};
+use crate::{deferred, Context, Field, Hasura, Render, Result, Serialized, Variable};
+
+/// Values keyed by column, as taken by the update operators.
+#[derive(Clone)]
+pub struct Columns<'a, T: Hasura> {
+    pub inner: Vec<(Field<'a, T>, Value)>,
+    error: Option<String>,
+}
+
+impl<'a, T: Hasura> Columns<'a, T> {
+    pub fn push(&mut self, field: Field<'a, T>, value: impl serde::Serialize) {
+        match serde_json::to_value(value) {
+            Ok(value) => self.inner.push((field, value)),
+            Err(error) => self.fail(error),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty() && self.error.is_none()
+    }
+    pub fn value(&self) -> Result<Value> {
+        deferred(&self.error)?;
+        let map: Map<_, _> = self.inner.iter().map(|(field, value)| (field.name.to_string(), value.clone())).collect();
+        Ok(Value::Object(map))
+    }
+    pub(crate) fn fail(&mut self, error: serde_json::Error) {
+        self.error.get_or_insert(error.to_string());
+    }
+    pub(crate) fn variable(&self, operator: &str) -> Variable<'_> {
+        Variable::new(format!("{}_{operator}_input!", T::table()), self, || self.value())
+    }
+}
+
+impl<'a, T: Hasura> Default for Columns<'a, T> {
+    fn default() -> Self {
+        Self { inner: vec![], error: None }
+    }
+}
+
+impl<'a, T: Hasura> Render for Columns<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        deferred(&self.error)?;
+        let inner = self
+            .inner
+            .iter()
+            .map(|(field, value)| Ok(format!("{}: {}", field.name, Serialized(value).render(context)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(format!("{{{}}}", inner.join(", ")))
+    }
+}
+
+/// The numeric and jsonb update operators of `Update`, `UpdateByPk` and each entry of `UpdateMany`.
+#[derive(Clone)]
+pub struct Operators<'a, T: Hasura> {
+    pub inc: Columns<'a, T>,
+    pub append: Columns<'a, T>,
+    pub prepend: Columns<'a, T>,
+    pub delete_key: Columns<'a, T>,
+    pub delete_elem: Columns<'a, T>,
+    pub delete_at_path: Columns<'a, T>,
+}
+
+impl<'a, T: Hasura> Default for Operators<'a, T> {
+    fn default() -> Self {
+        Self {
+            inc: Columns::default(),
+            append: Columns::default(),
+            prepend: Columns::default(),
+            delete_key: Columns::default(),
+            delete_elem: Columns::default(),
+            delete_at_path: Columns::default(),
+        }
+    }
+}
+
+impl<'a, T: Hasura> Operators<'a, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn inc(mut self, field: Field<'a, T>, value: impl serde::Serialize) -> Self {
+        self.inc.push(field, value);
+        self
+    }
+    pub fn append(mut self, field: Field<'a, T>, value: impl serde::Serialize) -> Self {
+        self.append.push(field, value);
+        self
+    }
+    pub fn prepend(mut self, field: Field<'a, T>, value: impl serde::Serialize) -> Self {
+        self.prepend.push(field, value);
+        self
+    }
+    pub fn delete_key(mut self, field: Field<'a, T>, key: &str) -> Self {
+        self.delete_key.push(field, key);
+        self
+    }
+    pub fn delete_elem(mut self, field: Field<'a, T>, index: i64) -> Self {
+        self.delete_elem.push(field, index);
+        self
+    }
+    pub fn delete_at_path(mut self, field: Field<'a, T>, path: Vec<&str>) -> Self {
+        self.delete_at_path.push(field, path);
+        self
+    }
+
+    // The operators that were given a column, with their argument key and input type suffix.
+    pub(crate) fn present(&self) -> impl Iterator<Item = (&'static str, &'static str, &Columns<'a, T>)> {
+        let operators = [
+            ("_inc", "inc", &self.inc),
+            ("_append", "append", &self.append),
+            ("_prepend", "prepend", &self.prepend),
+            ("_delete_key", "delete_key", &self.delete_key),
+            ("_delete_elem", "delete_elem", &self.delete_elem),
+            ("_delete_at_path", "delete_at_path", &self.delete_at_path),
+        ];
+        operators.into_iter().filter(|(_, _, columns)| !columns.is_empty())
+    }
+    pub(crate) fn params(&self) -> Vec<(&'static str, Variable<'_>)> {
+        self.present().map(|(key, operator, columns)| (key, columns.variable(operator))).collect()
+    }
+}
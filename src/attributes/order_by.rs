@@ -1,5 +1,28 @@
 use std::fmt::{Display, Formatter};
-use crate::{Field, Hasura};
+use crate::{Field, Fields, Hasura};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    AscNullsFirst,
+    AscNullsLast,
+    Desc,
+    DescNullsFirst,
+    DescNullsLast,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Asc => write!(f, "asc"),
+            Direction::AscNullsFirst => write!(f, "asc_nulls_first"),
+            Direction::AscNullsLast => write!(f, "asc_nulls_last"),
+            Direction::Desc => write!(f, "desc"),
+            Direction::DescNullsFirst => write!(f, "desc_nulls_first"),
+            Direction::DescNullsLast => write!(f, "desc_nulls_last"),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub enum OrderBy<'a, T: Hasura> {
@@ -9,17 +32,58 @@ pub enum OrderBy<'a, T: Hasura> {
     Desc(Field<'a, T>),
     DescNullsFirst(Field<'a, T>),
     DescNullsLast(Field<'a, T>),
+    Relation(Field<'a, T>, String),
+    Aggregate(Field<'a, T>, String),
+}
+
+impl<'a, T: Hasura> OrderBy<'a, T> {
+    pub fn relation<S: Hasura>(relation: impl FnOnce(Fields<'a, S>) -> Field<'a, T>, order_by: OrderBy<'a, S>) -> Self {
+        let field = relation(Fields { inner: vec![] });
+        Self::Relation(field, order_by.to_string())
+    }
+    pub fn aggregate<S: Hasura>(
+        relation: impl FnOnce(Fields<'a, S>) -> Field<'a, T>,
+        order_by: AggregateOrderBy<'a, S>,
+    ) -> Self {
+        let field = relation(Fields { inner: vec![] });
+        Self::Aggregate(field, order_by.to_string())
+    }
 }
 
 impl<'a, T: Hasura> Display for OrderBy<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (field, direction) = match self {
+            OrderBy::Asc(field) => (field, Direction::Asc),
+            OrderBy::AscNullsFirst(field) => (field, Direction::AscNullsFirst),
+            OrderBy::AscNullsLast(field) => (field, Direction::AscNullsLast),
+            OrderBy::Desc(field) => (field, Direction::Desc),
+            OrderBy::DescNullsFirst(field) => (field, Direction::DescNullsFirst),
+            OrderBy::DescNullsLast(field) => (field, Direction::DescNullsLast),
+            OrderBy::Relation(field, inner) => return write!(f, "{{{}: {inner}}}", field.name),
+            OrderBy::Aggregate(field, inner) => return write!(f, "{{{}_aggregate: {inner}}}", field.name),
+        };
+        write!(f, "{{{}: {direction}}}", field.name)
+    }
+}
+
+/// Ordering by an aggregate of an array relationship.
+#[derive(Clone)]
+pub enum AggregateOrderBy<'a, S: Hasura> {
+    Count(Direction),
+    Sum(OrderBy<'a, S>),
+    Avg(OrderBy<'a, S>),
+    Max(OrderBy<'a, S>),
+    Min(OrderBy<'a, S>),
+}
+
+impl<'a, S: Hasura> Display for AggregateOrderBy<'a, S> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            OrderBy::Asc(field) => write!(f, "{field}: asc"),
-            OrderBy::AscNullsFirst(field) => write!(f, "{field}: asc_nulls_first"),
-            OrderBy::AscNullsLast(field) => write!(f, "{field}: asc_nulls_last"),
-            OrderBy::Desc(field) => write!(f, "{field}: desc"),
-            OrderBy::DescNullsFirst(field) => write!(f, "{field}: desc_nulls_first"),
-            OrderBy::DescNullsLast(field) => write!(f, "{field}: desc_nulls_last"),
+            AggregateOrderBy::Count(direction) => write!(f, "{{count: {direction}}}"),
+            AggregateOrderBy::Sum(order_by) => write!(f, "{{sum: {order_by}}}"),
+            AggregateOrderBy::Avg(order_by) => write!(f, "{{avg: {order_by}}}"),
+            AggregateOrderBy::Max(order_by) => write!(f, "{{max: {order_by}}}"),
+            AggregateOrderBy::Min(order_by) => write!(f, "{{min: {order_by}}}"),
         }
     }
 }
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use crate::{impl_cond, ScalarValue};
+
+pub type Position = Vec<f64>;
+
+/// A GeoJSON geometry, as taken and returned by `geometry` and `geography` columns.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum Geometry {
+    Point { coordinates: Position },
+    MultiPoint { coordinates: Vec<Position> },
+    LineString { coordinates: Vec<Position> },
+    MultiLineString { coordinates: Vec<Vec<Position>> },
+    Polygon { coordinates: Vec<Vec<Position>> },
+    MultiPolygon { coordinates: Vec<Vec<Vec<Position>>> },
+    GeometryCollection { geometries: Vec<Geometry> },
+}
+
+impl Geometry {
+    pub fn point(x: f64, y: f64) -> Self {
+        Self::Point { coordinates: vec![x, y] }
+    }
+}
+
+impl ScalarValue for Geometry {}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DWithin {
+    pub distance: f64,
+    pub from: Geometry,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_spheroid: Option<bool>,
+}
+
+impl DWithin {
+    pub fn new(distance: f64, from: Geometry) -> Self {
+        Self { distance, from, use_spheroid: None }
+    }
+    pub fn use_spheroid(mut self, use_spheroid: bool) -> Self {
+        self.use_spheroid = Some(use_spheroid);
+        self
+    }
+}
+
+impl_cond!(StContains,_st_contains;Geometry);
+impl_cond!(StCrosses,_st_crosses;Geometry);
+impl_cond!(StEquals,_st_equals;Geometry);
+impl_cond!(StIntersects,_st_intersects;Geometry);
+impl_cond!(StOverlaps,_st_overlaps;Geometry);
+impl_cond!(StTouches,_st_touches;Geometry);
+impl_cond!(StWithin,_st_within;Geometry);
+impl_cond!(StDWithin,_st_d_within;DWithin);
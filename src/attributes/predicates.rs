@@ -0,0 +1,73 @@
+use itertools::Itertools;
+use serde_json::{Map, Value};
+use crate::{Braced, Condition, Conditions, Context, Expression, Hasura, Render, Result};
+
+/// The `count` predicate of an `<relation>_aggregate` filter.
+pub struct Count<'a, S: Hasura> {
+    pub predicate: Box<dyn Condition>,
+    pub filter: Option<Conditions<'a, S>>,
+    pub distinct: Option<bool>,
+    pub arguments: Vec<S::Column>,
+}
+
+impl<'a, S: Hasura> Count<'a, S> {
+    pub fn new(predicate: impl Condition + 'static) -> Self {
+        Self {
+            predicate: Box::new(predicate),
+            filter: None,
+            distinct: None,
+            arguments: vec![],
+        }
+    }
+    pub fn filter(mut self, filter: Conditions<'a, S>) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+    pub fn distinct(mut self, distinct: bool) -> Self {
+        self.distinct = Some(distinct);
+        self
+    }
+    pub fn arguments(mut self, arguments: Vec<S::Column>) -> Self {
+        self.arguments = arguments;
+        self
+    }
+}
+
+impl<'a, S: Hasura> Expression for Count<'a, S> {
+    fn value(&self) -> Result<Value> {
+        let mut predicate = Map::new();
+        predicate.insert(self.predicate.operator().to_string(), self.predicate.value()?);
+
+        let mut count = Map::new();
+        count.insert("predicate".to_string(), Value::Object(predicate));
+        if let Some(filter) = &self.filter {
+            count.insert("filter".to_string(), filter.value()?);
+        }
+        if let Some(distinct) = self.distinct {
+            count.insert("distinct".to_string(), Value::Bool(distinct));
+        }
+        if !self.arguments.is_empty() {
+            let arguments = self.arguments.iter().map(|column| Value::String(column.to_string()));
+            count.insert("arguments".to_string(), Value::Array(arguments.collect()));
+        }
+
+        Ok(serde_json::json!({ "count": count }))
+    }
+}
+
+impl<'a, S: Hasura> Render for Count<'a, S> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let mut inner = vec![format!("predicate: {}", Braced(&self.predicate).render(context)?)];
+        if let Some(filter) = &self.filter {
+            inner.push(format!("filter: {}", Braced(filter).render(context)?));
+        }
+        if let Some(distinct) = self.distinct {
+            inner.push(format!("distinct: {distinct}"));
+        }
+        if !self.arguments.is_empty() {
+            inner.push(format!("arguments: [{}]", self.arguments.iter().join(", ")));
+        }
+
+        Ok(format!("count: {{ {} }}", inner.join(", ")))
+    }
+}
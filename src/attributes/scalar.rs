@@ -0,0 +1,22 @@
+/// A value that can be compared against a column, rendered as a GraphQL literal.
+///
+/// Implement this for newtypes that serialize as one of the scalars below
+/// to use them with every comparison operator.
+pub trait ScalarValue: serde::Serialize {}
+
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(impl ScalarValue for $t {})*
+    };
+}
+
+impl_scalar!(bool, char, str, String);
+impl_scalar!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+impl_scalar!(serde_json::Value);
+impl_scalar!(chrono::NaiveDate, chrono::NaiveTime, chrono::NaiveDateTime);
+
+impl<T: ScalarValue + ?Sized> ScalarValue for &T {}
+
+impl<T: ScalarValue> ScalarValue for Option<T> {}
+
+impl<Tz: chrono::TimeZone> ScalarValue for chrono::DateTime<Tz> where Self: serde::Serialize {}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use serde_json::Value;
+use crate::{to_row, Columns, Context, Field, Hasura, Render, Result, Serialized};
+
+/// The `_set` argument of an update, either a whole row or only the chosen columns.
+pub enum Set<'a, T: Hasura> {
+    Object(&'a T),
+    Columns(Columns<'a, T>),
+}
+
+impl<'a, T: Hasura + Serialize> Set<'a, T> {
+    pub fn new() -> Self {
+        Self::Columns(Columns::default())
+    }
+
+    pub fn set(self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        let mut columns = self.into_columns();
+        columns.push(field, value);
+        Self::Columns(columns)
+    }
+    pub fn null(self, field: Field<'a, T>) -> Self {
+        self.set(field, Value::Null)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Columns(columns) if columns.is_empty())
+    }
+
+    pub fn value(&self) -> Result<Value> {
+        match self {
+            Self::Object(object) => Ok(to_row(*object)?),
+            Self::Columns(columns) => columns.value(),
+        }
+    }
+
+    fn into_columns(self) -> Columns<'a, T> {
+        let object = match self {
+            Self::Object(object) => object,
+            Self::Columns(columns) => return columns,
+        };
+
+        let mut columns = Columns::default();
+        match to_row(object) {
+            Ok(Value::Object(map)) => {
+                for (name, value) in map {
+                    if let Some(field) = T::all().inner.into_iter().find(|field| field.name == name && field.nested.is_none()) {
+                        columns.push(field, value);
+                    }
+                }
+            }
+            Ok(_) => (),
+            Err(error) => columns.fail(error),
+        }
+        columns
+    }
+}
+
+impl<'a, T: Hasura + Serialize> Default for Set<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: Hasura> From<&'a T> for Set<'a, T> {
+    fn from(object: &'a T) -> Self {
+        Self::Object(object)
+    }
+}
+
+impl<'a, T: Hasura> From<Columns<'a, T>> for Set<'a, T> {
+    fn from(columns: Columns<'a, T>) -> Self {
+        Self::Columns(columns)
+    }
+}
+
+impl<'a, T: Hasura + Serialize> Render for Set<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        match self {
+            Self::Object(object) => Serialized(*object).render(context),
+            Self::Columns(columns) => columns.render(context),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use serde_json::Value;
+
+use crate::segments::decode;
+use crate::{Context, Hasura, Mutation, Queryable, Render, Result};
+#[cfg(feature = "subscriptions")]
+use crate::{Query, QueryAggregate, QueryByPk, Subscribable};
+#[cfg(feature = "subscriptions")]
+use serde::de::DeserializeOwned;
+
+/// Several query roots sent as one operation, either a tuple of queries or a `Vec` of the same query.
+pub trait Queries<P> {
+    type Out;
+    fn roots(&self, context: &mut Context) -> Result<String>;
+    fn keys(&self) -> Vec<String>;
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out>;
+}
+
+/// Several subscription roots sent as one operation, either a tuple or a `Vec` of the same root.
+#[cfg(feature = "subscriptions")]
+pub trait Subscriptions<P> {
+    type Out;
+    fn roots(&self, context: &mut Context) -> Result<String>;
+    fn keys(&self) -> Vec<String>;
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out>;
+}
+
+/// Several mutation roots sent as one operation, either a tuple of mutations or a `Vec` of the same mutation.
+pub trait Mutations<P> {
+    type Out;
+    fn roots(&self, context: &mut Context) -> Result<String>;
+    fn keys(&self) -> Vec<String>;
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out>;
+}
+
+impl<P: Hasura, T: Queryable<P>> Queryable<P> for &T {
+    type Out = T::Out;
+    fn name() -> String {
+        T::name()
+    }
+    fn key(&self) -> String {
+        (**self).key()
+    }
+}
+
+impl<P: Hasura, T: Mutation<P>> Mutation<P> for &T {
+    type Out = T::Out;
+    fn name() -> String {
+        T::name()
+    }
+    fn key(&self) -> String {
+        (**self).key()
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+impl<P: Hasura, T: Subscribable<P>> Subscribable<P> for &T {
+    type Out = T::Out;
+    fn name() -> String {
+        T::name()
+    }
+    fn key(&self) -> String {
+        (**self).key()
+    }
+}
+
+// Every query can be subscribed to as a live query.
+#[cfg(feature = "subscriptions")]
+macro_rules! impl_subscribable {
+    ($($t:ident),+) => {
+        $(impl<'a, T: Hasura + DeserializeOwned> Subscribable<T> for $t<'a, T> {
+            type Out = <Self as Queryable<T>>::Out;
+            fn name() -> String {
+                <Self as Queryable<T>>::name()
+            }
+            fn key(&self) -> String {
+                Queryable::key(self)
+            }
+        })+
+    };
+}
+
+#[cfg(feature = "subscriptions")]
+impl_subscribable!(Query, QueryByPk, QueryAggregate);
+
+macro_rules! impl_tuple {
+    ($(($p:ident, $t:ident, $i:tt)),+) => {
+        impl<$($p: Hasura, $t: Queryable<$p>),+> Queries<($($p,)+)> for ($($t,)+) {
+            type Out = ($(<$t as Queryable<$p>>::Out,)+);
+            fn roots(&self, context: &mut Context) -> Result<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Queryable<$p>>::name())),+]);
+                Ok([$(aliased(self.$i.render(context)?, &keys[$i])),+].join(" "))
+            }
+            fn keys(&self) -> Vec<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Queryable<$p>>::name())),+]);
+                keys.into_iter().map(|(key, _)| key).collect()
+            }
+            fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+                Ok(($(decode(value, &keys[$i])?,)+))
+            }
+        }
+
+        impl<$($p: Hasura, $t: Mutation<$p>),+> Mutations<($($p,)+)> for ($($t,)+) {
+            type Out = ($(<$t as Mutation<$p>>::Out,)+);
+            fn roots(&self, context: &mut Context) -> Result<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Mutation<$p>>::name())),+]);
+                Ok([$(aliased(self.$i.render(context)?, &keys[$i])),+].join(" "))
+            }
+            fn keys(&self) -> Vec<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Mutation<$p>>::name())),+]);
+                keys.into_iter().map(|(key, _)| key).collect()
+            }
+            fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+                Ok(($(decode(value, &keys[$i])?,)+))
+            }
+        }
+
+        #[cfg(feature = "subscriptions")]
+        impl<$($p: Hasura, $t: Subscribable<$p>),+> Subscriptions<($($p,)+)> for ($($t,)+) {
+            type Out = ($(<$t as Subscribable<$p>>::Out,)+);
+            fn roots(&self, context: &mut Context) -> Result<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Subscribable<$p>>::name())),+]);
+                Ok([$(aliased(self.$i.render(context)?, &keys[$i])),+].join(" "))
+            }
+            fn keys(&self) -> Vec<String> {
+                let keys = batch_keys(vec![$((self.$i.key(), <$t as Subscribable<$p>>::name())),+]);
+                keys.into_iter().map(|(key, _)| key).collect()
+            }
+            fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+                Ok(($(decode(value, &keys[$i])?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!((P0, T0, 0));
+impl_tuple!((P0, T0, 0), (P1, T1, 1));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6));
+impl_tuple!((P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7));
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10), (P11, T11, 11)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10), (P11, T11, 11), (P12, T12, 12)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10), (P11, T11, 11), (P12, T12, 12), (P13, T13, 13)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10), (P11, T11, 11), (P12, T12, 12), (P13, T13, 13), (P14, T14, 14)
+);
+impl_tuple!(
+    (P0, T0, 0), (P1, T1, 1), (P2, T2, 2), (P3, T3, 3), (P4, T4, 4), (P5, T5, 5), (P6, T6, 6), (P7, T7, 7),
+    (P8, T8, 8), (P9, T9, 9), (P10, T10, 10), (P11, T11, 11), (P12, T12, 12), (P13, T13, 13), (P14, T14, 14),
+    (P15, T15, 15)
+);
+
+// Roots that kept their default name get aliased by position when another root shares it, so they don't collide.
+fn batch_keys(roots: Vec<(String, String)>) -> Vec<(String, bool)> {
+    let shared = |key: &str| roots.iter().filter(|(other, _)| other == key).count() > 1;
+    roots
+        .iter()
+        .enumerate()
+        .map(|(index, (key, name))| match key == name && shared(key) {
+            true => (format!("{name}_{index}"), true),
+            false => (key.clone(), false),
+        })
+        .collect()
+}
+
+fn aliased(root: String, (key, aliased): &(String, bool)) -> String {
+    match aliased {
+        true => format!("{key}: {root}"),
+        false => root,
+    }
+}
+
+fn batch_roots<R: Render>(batch: &[R], keys: Vec<(String, bool)>, context: &mut Context) -> Result<String> {
+    let roots = batch.iter().zip(&keys).map(|(root, key)| Ok(aliased(root.render(context)?, key)));
+    Ok(roots.collect::<Result<Vec<_>>>()?.join(" "))
+}
+
+impl<P: Hasura, Q: Queryable<P>> Queries<P> for Vec<Q> {
+    type Out = Vec<Q::Out>;
+    fn roots(&self, context: &mut Context) -> Result<String> {
+        batch_roots(self, batch_keys(self.iter().map(|root| (root.key(), Q::name())).collect()), context)
+    }
+    fn keys(&self) -> Vec<String> {
+        let keys = batch_keys(self.iter().map(|root| (root.key(), Q::name())).collect());
+        keys.into_iter().map(|(key, _)| key).collect()
+    }
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+        keys.iter().map(|key| decode(value, key)).collect()
+    }
+}
+
+impl<P: Hasura, M: Mutation<P>> Mutations<P> for Vec<M> {
+    type Out = Vec<M::Out>;
+    fn roots(&self, context: &mut Context) -> Result<String> {
+        batch_roots(self, batch_keys(self.iter().map(|root| (root.key(), M::name())).collect()), context)
+    }
+    fn keys(&self) -> Vec<String> {
+        let keys = batch_keys(self.iter().map(|root| (root.key(), M::name())).collect());
+        keys.into_iter().map(|(key, _)| key).collect()
+    }
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+        keys.iter().map(|key| decode(value, key)).collect()
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+impl<P: Hasura, S: Subscribable<P>> Subscriptions<P> for Vec<S> {
+    type Out = Vec<S::Out>;
+    fn roots(&self, context: &mut Context) -> Result<String> {
+        batch_roots(self, batch_keys(self.iter().map(|root| (root.key(), S::name())).collect()), context)
+    }
+    fn keys(&self) -> Vec<String> {
+        let keys = batch_keys(self.iter().map(|root| (root.key(), S::name())).collect());
+        keys.into_iter().map(|(key, _)| key).collect()
+    }
+    fn decode(value: &Value, keys: &[String]) -> Result<Self::Out> {
+        keys.iter().map(|key| decode(value, key)).collect()
+    }
+}
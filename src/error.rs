@@ -1,19 +1,33 @@
 use crate::fetcher::GraphqlError;
+use crate::LiteralError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Request error: {0}")]
     Request(#[from] reqwest::Error),
 
+    #[cfg(feature = "subscriptions")]
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite_wasm::Error),
+
     #[error("Serde error: {0}")]
     Serde(#[from] serde_json::Error),
 
+    #[error("Literal error: {0}")]
+    Literal(#[from] LiteralError),
+
     #[error("Hasura error: {0:?}")]
     Hasura(Vec<GraphqlError>),
 
     #[error("Endpoint not found: code={code} path={path}")]
     NotFound{code: String, path: String},
 
+    #[error("Subscription protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Unconditional mutation: {0} has no conditions, use all_rows() to affect every row")]
+    Unconditional(String),
+
     #[error("Internal empty error")]
     Empty,
 }
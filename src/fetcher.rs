@@ -5,7 +5,7 @@ use std::str::FromStr;
 use reqwest::header::{HeaderName, HeaderValue};
 use serde_json::Value;
 
-use crate::{Error, Result};
+use crate::{Context, Error, Result};
 
 #[derive(serde::Deserialize, Debug)]
 pub struct GraphqlError {
@@ -15,7 +15,7 @@ pub struct GraphqlError {
 
 #[derive(serde::Deserialize, Debug)]
 #[serde(untagged)]
-enum Response {
+pub(crate) enum Response {
     Data {
         data: Value,
     },
@@ -28,6 +28,16 @@ enum Response {
     },
 }
 
+impl Response {
+    pub(crate) fn data(self) -> Result<Value> {
+        match self {
+            Response::Data { data } => Ok(data),
+            Response::Errors { errors } => Err(Error::Hasura(errors)),
+            Response::NotFound { path, code, .. } => Err(Error::NotFound { path, code }),
+        }
+    }
+}
+
 async fn request(url: &str, body: String, headers: HashMap<String, String>) -> Result<String> {
     let mut temp = reqwest::header::HeaderMap::new();
     for (key, value) in headers {
@@ -62,12 +72,8 @@ impl<O> Display for Wrapper<O> {
 
 impl<O> Wrapper<O> {
     pub fn parse(self) -> Result<O> {
-        let val = match serde_json::from_str(&self.text)? {
-            Response::Data { data } => Ok(data),
-            Response::Errors { errors } => Err(Error::Hasura(errors)),
-            Response::NotFound { path, code, .. } => Err(Error::NotFound { path, code }),
-        };
-        (self.extract)(val?)
+        let response: Response = serde_json::from_str(&self.text)?;
+        (self.extract)(response.data()?)
     }
 }
 
@@ -75,17 +81,28 @@ pub struct Fetcher<O> {
     pub body: String,
     pub extract: Box<dyn FnOnce(Value) -> Result<O>>,
     pub headers: HashMap<String, String>,
+    pub error: Option<Error>,
 }
 
 impl<O> Fetcher<O> {
-    pub fn new<Fn: FnOnce(Value) -> Result<O> + 'static>(body: String, extract: Fn) -> Self {
-        let inner = body.to_string().replace('"', "\\\"");
-        let body = format!("{{\"query\":\"{inner}\"}}");
+    pub fn new<Fn: FnOnce(Value) -> Result<O> + 'static>(operation: &str, roots: String, context: Context, extract: Fn) -> Self {
+        let (query, variables) = context.operation(operation, roots);
+        let body = serde_json::json!({"query": query, "variables": variables}).to_string();
 
         Self {
             body,
             extract: Box::new(extract),
             headers: HashMap::new(),
+            error: None,
+        }
+    }
+
+    pub(crate) fn failed(error: Error) -> Self {
+        Self {
+            body: String::new(),
+            extract: Box::new(|_| Err(Error::Empty)),
+            headers: HashMap::new(),
+            error: Some(error),
         }
     }
 
@@ -109,6 +126,10 @@ impl<O> Fetcher<O> {
     }
 
     pub async fn send(self, url: &str) -> Result<Wrapper<O>> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
         Ok(Wrapper {
             text: request(url, self.body, self.headers).await?,
             extract: self.extract,
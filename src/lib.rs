@@ -2,12 +2,22 @@ pub use attributes::*;
 pub use queries::*;
 
 pub use segments::*;
+pub use batch::{Mutations, Queries};
+#[cfg(feature = "subscriptions")]
+pub use batch::Subscriptions;
 pub use error::*;
 pub use fetcher::Fetcher;
-pub use traits::Hasura;
+#[cfg(feature = "subscriptions")]
+pub use subscriber::Subscriber;
+pub use traits::{Hasura, Render};
 pub(crate) use traits::{Queryable, Mutation};
+#[cfg(feature = "subscriptions")]
+pub(crate) use traits::Subscribable;
 pub(crate) use utils::builder::*;
 pub(crate) use utils::serializer::to_string;
+pub use utils::serializer::Error as LiteralError;
+pub(crate) use utils::variables::{deferred, to_row, PkArgs, Variable};
+pub use utils::variables::Context;
 
 pub use derive::Hasura;
 
@@ -36,8 +46,11 @@ pub mod relation {
 
 mod attributes;
 mod segments;
+mod batch;
 mod error;
 mod fetcher;
+#[cfg(feature = "subscriptions")]
+mod subscriber;
 mod macros;
 mod traits;
 mod queries;
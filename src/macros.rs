@@ -1,26 +1,49 @@
 #[macro_export]
 macro_rules! query {
-    ($a:ident) => {
+    ($c:expr; $a:expr) => {
+        $c.query1(&$a)
+    };
+    ($c:expr; $($a:expr),+ $(,)?) => {
+        $c.query(&($(&$a,)+))
+    };
+    ($a:expr) => {
         hasura::query1(&$a)
     };
-    ($a:ident, $b:ident) => {
-        hasura::query2(&$a, &$b)
+    ($($a:expr),+ $(,)?) => {
+        hasura::query(&($(&$a,)+))
+    };
+}
+
+#[cfg(feature = "subscriptions")]
+#[macro_export]
+macro_rules! subscription {
+    ($c:expr; $a:expr) => {
+        $c.subscription1(&$a)
     };
-        ($a:ident, $b:ident, $c:ident) => {
-        hasura::query3(&$a, &$b, &$c)
+    ($c:expr; $($a:expr),+ $(,)?) => {
+        $c.subscription(&($(&$a,)+))
+    };
+    ($a:expr) => {
+        hasura::subscription1(&$a)
+    };
+    ($($a:expr),+ $(,)?) => {
+        hasura::subscription(&($(&$a,)+))
     };
 }
 
 #[macro_export]
 macro_rules! mutation {
-    ($a:ident) => {
-        hasura::mutation1(&$a)
+    ($c:expr; $a:expr) => {
+        $c.mutation1(&$a)
     };
-    ($a:ident, $b:ident) => {
-        hasura::mutation2(&$a, &$b)
+    ($c:expr; $($a:expr),+ $(,)?) => {
+        $c.mutation(&($(&$a,)+))
+    };
+    ($a:expr) => {
+        hasura::mutation1(&$a)
     };
-    ($a:ident, $b:ident, $c:ident) => {
-        hasura::mutation3(&$a, &$b, &$c)
+    ($($a:expr),+ $(,)?) => {
+        hasura::mutation(&($(&$a,)+))
     };
 }
 
@@ -1,25 +1,32 @@
-use crate::{Braced, Builder, Conditions, Fields, Hasura, Mutation};
+use crate::{Builder, Conditions, Context, Error, Fields, Hasura, Mutation, MutationResponse, Render, Result};
 use serde::de::DeserializeOwned;
-use std::fmt::{Display, Formatter};
 
 pub struct Delete<'a, T: Hasura> {
     pub conditions: Conditions<'a, T>,
+    pub all_rows: bool,
     pub affected_rows: bool,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> Delete<'a, T> {
     pub fn new() -> Self {
         Self {
             conditions: Conditions::None,
+            all_rows: false,
             affected_rows: false,
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
         self.conditions = conditions;
         self
     }
+    pub fn all_rows(mut self) -> Self {
+        self.all_rows = true;
+        self
+    }
     pub fn affected_rows(mut self, affected_rows: bool) -> Self {
         self.affected_rows = affected_rows;
         self
@@ -28,20 +35,39 @@ impl<'a, T: Hasura> Delete<'a, T> {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura> Default for Delete<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned> Mutation<T> for Delete<'a, T> {
-    type Out = Vec<T>;
+    type Out = MutationResponse<T>;
     fn name() -> String {
         format!("delete_{}", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned> Display for Delete<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned> Render for Delete<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        if !self.all_rows && self.conditions.is_unconditional() {
+            return Err(Error::Unconditional(Self::name()));
+        }
+
         Builder::new(Self::name(), &self.returning)
-            .param("where", &Braced(&self.conditions))
+            .alias(self.alias)
+            .input("where", &self.conditions.variable())
+            .affected(self.affected_rows)
             .explicit(true)
-            .write(f)
+            .render(context)
     }
 }
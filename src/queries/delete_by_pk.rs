@@ -1,11 +1,11 @@
-use std::fmt::{Display, Formatter};
 use serde::de::DeserializeOwned;
-use crate::{Builder, Fields, Hasura, Mutation, Serialized};
+use crate::{Builder, Context, Fields, Hasura, Mutation, PkArgs, Render, Result};
 
 pub struct DeleteByPk<'a, T: Hasura> {
     pub pk: T::Pk,
     pub affected_rows: bool,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> DeleteByPk<'a, T> {
@@ -14,12 +14,17 @@ impl<'a, T: Hasura> DeleteByPk<'a, T> {
             pk,
             affected_rows: false,
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn returning(mut self, returning: Fields<'a, T>) -> Self {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned> Mutation<T> for DeleteByPk<'a, T> {
@@ -27,12 +32,16 @@ impl<'a, T: Hasura + DeserializeOwned> Mutation<T> for DeleteByPk<'a, T> {
     fn name() -> String {
         format!("delete_{}_by_pk", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned> Display for DeleteByPk<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned> Render for DeleteByPk<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
         Builder::new(Self::name(), &self.returning)
-            .pk(&Serialized(&self.pk))
-            .write(f)
+            .alias(self.alias)
+            .pk(&PkArgs::<T>(&self.pk))
+            .render(context)
     }
-}
\ No newline at end of file
+}
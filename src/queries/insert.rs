@@ -1,14 +1,14 @@
 use crate::Separalized;
-use crate::{Builder, Fields, Hasura, Mutation, OnConflict};
+use crate::{to_row, Builder, Context, Fields, Hasura, Mutation, MutationResponse, OnConflict, Render, Result, Variable};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::fmt::Formatter;
 
 pub struct Insert<'a, T: Hasura> {
     pub objects: &'a [T],
     pub affected_rows: bool,
-    pub on_conflict: Option<OnConflict>,
+    pub on_conflict: Option<OnConflict<'a, T>>,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> Insert<'a, T> {
@@ -18,13 +18,14 @@ impl<'a, T: Hasura> Insert<'a, T> {
             affected_rows: false,
             on_conflict: None,
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn affected_rows(mut self, affected_rows: bool) -> Self {
         self.affected_rows = affected_rows;
         self
     }
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+    pub fn on_conflict(mut self, on_conflict: OnConflict<'a, T>) -> Self {
         self.on_conflict = Some(on_conflict);
         self
     }
@@ -32,22 +33,35 @@ impl<'a, T: Hasura> Insert<'a, T> {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned + Serialize> Mutation<T> for Insert<'a, T> {
-    type Out = Vec<T>;
+    type Out = MutationResponse<T>;
     fn name() -> String {
         format!("insert_{}", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned + Serialize> std::fmt::Display for Insert<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned + Serialize> Render for Insert<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let objects = (!self.objects.is_empty()).then(|| {
+            let ty = format!("[{}_insert_input!]!", T::table());
+            Variable::new(ty, Separalized(self.objects), || Ok(to_row(self.objects)?))
+        });
+
         Builder::new(Self::name(), &self.returning)
-            .maybe("objects", &Separalized(self.objects.as_ref()))
-            .maybe("on_conflict", &self.on_conflict)
+            .alias(self.alias)
+            .maybe_input("objects", &objects)
+            .maybe_input("on_conflict", &self.on_conflict)
             .affected(self.affected_rows)
             .explicit(true)
-            .write(f)
+            .render(context)
     }
 }
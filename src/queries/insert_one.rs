@@ -1,13 +1,13 @@
-use std::fmt::Formatter;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use crate::{Builder, Fields, Hasura, Mutation, OnConflict};
+use crate::{to_row, Builder, Context, Fields, Hasura, Mutation, OnConflict, Render, Result, Variable};
 use crate::Serialized;
 
 pub struct InsertOne<'a, T: Hasura> {
     pub object: &'a T,
-    pub on_conflict: Option<OnConflict>,
+    pub on_conflict: Option<OnConflict<'a, T>>,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> InsertOne<'a, T> {
@@ -16,9 +16,10 @@ impl<'a, T: Hasura> InsertOne<'a, T> {
             object,
             on_conflict: None,
             returning: T::all(),
+            alias: None,
         }
     }
-    pub fn on_conflict(mut self, on_conflict: OnConflict) -> Self {
+    pub fn on_conflict(mut self, on_conflict: OnConflict<'a, T>) -> Self {
         self.on_conflict = Some(on_conflict);
         self
     }
@@ -26,18 +27,28 @@ impl<'a, T: Hasura> InsertOne<'a, T> {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned + Serialize> Mutation<T> for InsertOne<'a, T> {
     type Out = Option<T>;
     fn name() -> String { format!("insert_{}_one", T::table()) }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned + Serialize> std::fmt::Display for InsertOne<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned + Serialize> Render for InsertOne<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let object = Variable::new(format!("{}_insert_input!", T::table()), Serialized(self.object), || Ok(to_row(self.object)?));
+
         Builder::new(Self::name(), &self.returning)
-            .param("object", &Serialized(&self.object))
-            .maybe("on_conflict", &self.on_conflict)
-            .write(f)
+            .alias(self.alias)
+            .input("object", &object)
+            .maybe_input("on_conflict", &self.on_conflict)
+            .render(context)
     }
-}
\ No newline at end of file
+}
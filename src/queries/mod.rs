@@ -1,17 +1,27 @@
 mod delete;
 mod insert;
 mod query;
+#[cfg(feature = "subscriptions")]
+mod stream;
 mod update;
+mod update_many;
+mod mutation_response;
 mod insert_one;
 mod query_by_pk;
+mod query_aggregate;
 mod delete_by_pk;
 mod update_by_pk;
 
 pub use delete::Delete;
 pub use insert::Insert;
 pub use query::Query;
+#[cfg(feature = "subscriptions")]
+pub use stream::Stream;
 pub use update::Update;
+pub use update_many::UpdateMany;
+pub use mutation_response::MutationResponse;
 pub use insert_one::InsertOne;
 pub use query_by_pk::QueryByPk;
+pub use query_aggregate::{Aggregate, AggregateValues, QueryAggregate};
 pub use delete_by_pk::DeleteByPk;
 pub use update_by_pk::UpdateByPk;
\ No newline at end of file
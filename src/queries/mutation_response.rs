@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct MutationResponse<T> {
+    pub affected_rows: Option<u64>,
+    #[serde(default)]
+    pub returning: Vec<T>,
+}
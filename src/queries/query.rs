@@ -1,14 +1,14 @@
-use crate::{Braced, Builder, Conditions, Field, Fields, Hasura, OrderBy, Queryable, Separated};
+use crate::{Builder, Conditions, Context, Fields, Hasura, Listed, OrderBy, Queryable, Render, Result};
 use serde::de::DeserializeOwned;
-use std::fmt::Formatter;
 
 pub struct Query<'a, T: Hasura> {
-    pub distinct_on: Option<Field<'a, T>>,
+    pub distinct_on: Option<T::Column>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
     pub conditions: Option<Conditions<'a, T>>,
     pub order_by: Vec<OrderBy<'a, T>>,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> Query<'a, T> {
@@ -20,10 +20,11 @@ impl<'a, T: Hasura> Query<'a, T> {
             conditions: None,
             order_by: vec![],
             returning: T::all(),
+            alias: None,
         }
     }
 
-    pub fn distinct_on(mut self, distinct_on: Field<'a, T>) -> Self {
+    pub fn distinct_on(mut self, distinct_on: T::Column) -> Self {
         self.distinct_on = Some(distinct_on);
         self
     }
@@ -47,6 +48,16 @@ impl<'a, T: Hasura> Query<'a, T> {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura> Default for Query<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned> Queryable<T> for Query<'a, T> {
@@ -54,21 +65,20 @@ impl<'a, T: Hasura + DeserializeOwned> Queryable<T> for Query<'a, T> {
     fn name() -> String {
         T::table().to_string()
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned> std::fmt::Display for Query<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned> Render for Query<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
         Builder::new(Self::name(), &self.returning)
-            .maybe("order_by", &Separated(self.order_by.as_ref()))
+            .alias(self.alias)
+            .maybe("order_by", &Listed(self.order_by.as_ref()))
             .maybe("distinct_on", &self.distinct_on)
             .maybe("limit", &self.limit)
             .maybe("offset", &self.offset)
-            .maybe("where", &self.conditions.as_ref().map(|x| Braced(x)))
-            .write(f)
+            .maybe_input("where", &self.conditions.as_ref().map(Conditions::variable))
+            .render(context)
     }
 }
-
-// TODO: understand this
-// pub struct QueryAggregate {
-//
-// }
@@ -0,0 +1,164 @@
+use crate::{AggregateFields, Builder, Conditions, Context, Field, Fields, Hasura, Listed, OrderBy, Queryable, Render, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AggregateValues {
+    pub count: Option<u64>,
+    #[serde(default)]
+    pub sum: Map<String, Value>,
+    #[serde(default)]
+    pub avg: Map<String, Value>,
+    #[serde(default)]
+    pub max: Map<String, Value>,
+    #[serde(default)]
+    pub min: Map<String, Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub struct Aggregate<T> {
+    #[serde(default)]
+    pub aggregate: AggregateValues,
+    #[serde(default)]
+    pub nodes: Vec<T>,
+}
+
+fn extract<V: DeserializeOwned>(values: &Map<String, Value>, name: &str) -> Result<Option<V>> {
+    match values.get(name) {
+        Some(value) => Ok(serde_json::from_value(value.clone())?),
+        None => Ok(None),
+    }
+}
+
+impl<T: Hasura> Aggregate<T> {
+    pub fn count(&self) -> Option<u64> {
+        self.aggregate.count
+    }
+    pub fn sum<V: DeserializeOwned>(&self, field: &Field<T>) -> Result<Option<V>> {
+        extract(&self.aggregate.sum, field.name)
+    }
+    pub fn avg<V: DeserializeOwned>(&self, field: &Field<T>) -> Result<Option<V>> {
+        extract(&self.aggregate.avg, field.name)
+    }
+    pub fn max<V: DeserializeOwned>(&self, field: &Field<T>) -> Result<Option<V>> {
+        extract(&self.aggregate.max, field.name)
+    }
+    pub fn min<V: DeserializeOwned>(&self, field: &Field<T>) -> Result<Option<V>> {
+        extract(&self.aggregate.min, field.name)
+    }
+}
+
+pub struct QueryAggregate<'a, T: Hasura> {
+    pub distinct_on: Option<T::Column>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub conditions: Option<Conditions<'a, T>>,
+    pub order_by: Vec<OrderBy<'a, T>>,
+    pub aggregate: AggregateFields<'a, T>,
+    pub nodes: Option<Fields<'a, T>>,
+    pub alias: Option<&'a str>,
+}
+
+impl<'a, T: Hasura> QueryAggregate<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            distinct_on: None,
+            limit: None,
+            offset: None,
+            conditions: None,
+            order_by: vec![],
+            aggregate: AggregateFields::default(),
+            nodes: None,
+            alias: None,
+        }
+    }
+
+    pub fn distinct_on(mut self, distinct_on: T::Column) -> Self {
+        self.distinct_on = Some(distinct_on);
+        self
+    }
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+    pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+    pub fn order_by(mut self, order_by: Vec<OrderBy<'a, T>>) -> Self {
+        self.order_by = order_by;
+        self
+    }
+    pub fn count(mut self, count: bool) -> Self {
+        self.aggregate.count = count;
+        self
+    }
+    pub fn sum(mut self, sum: Vec<Field<'a, T>>) -> Self {
+        self.aggregate.sum = sum;
+        self
+    }
+    pub fn avg(mut self, avg: Vec<Field<'a, T>>) -> Self {
+        self.aggregate.avg = avg;
+        self
+    }
+    pub fn max(mut self, max: Vec<Field<'a, T>>) -> Self {
+        self.aggregate.max = max;
+        self
+    }
+    pub fn min(mut self, min: Vec<Field<'a, T>>) -> Self {
+        self.aggregate.min = min;
+        self
+    }
+    pub fn nodes(mut self, nodes: Fields<'a, T>) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura> Default for QueryAggregate<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: Hasura + DeserializeOwned> Queryable<T> for QueryAggregate<'a, T> {
+    type Out = Aggregate<T>;
+    fn name() -> String {
+        format!("{}_aggregate", T::table())
+    }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
+}
+
+struct Selection<'b, 'a, T: Hasura>(&'b AggregateFields<'a, T>, &'b Option<Fields<'a, T>>);
+
+impl<'b, 'a, T: Hasura> Render for Selection<'b, 'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let nodes = self.1.as_ref().map(|nodes| nodes.render(context)).transpose()?;
+        Ok(self.0.selection(nodes))
+    }
+}
+
+impl<'a, T: Hasura + DeserializeOwned> Render for QueryAggregate<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        Builder::new(Self::name(), &Selection(&self.aggregate, &self.nodes))
+            .alias(self.alias)
+            .maybe("order_by", &Listed(self.order_by.as_ref()))
+            .maybe("distinct_on", &self.distinct_on)
+            .maybe("limit", &self.limit)
+            .maybe("offset", &self.offset)
+            .maybe_input("where", &self.conditions.as_ref().map(Conditions::variable))
+            .render(context)
+    }
+}
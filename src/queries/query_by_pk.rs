@@ -1,10 +1,10 @@
-use crate::{Builder, Fields, Flattened, Hasura, Queryable};
+use crate::{Builder, Context, Fields, Hasura, PkArgs, Queryable, Render, Result};
 use serde::de::DeserializeOwned;
-use std::fmt::{Display, Formatter};
 
 pub struct QueryByPk<'a, T: Hasura> {
     pub pk: T::Pk,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura> QueryByPk<'a, T> {
@@ -12,12 +12,17 @@ impl<'a, T: Hasura> QueryByPk<'a, T> {
         QueryByPk {
             pk,
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn returning(mut self, returning: Fields<'a, T>) -> Self {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
 }
 
 impl<'a, T: Hasura + DeserializeOwned> Queryable<T> for QueryByPk<'a, T> {
@@ -25,12 +30,16 @@ impl<'a, T: Hasura + DeserializeOwned> Queryable<T> for QueryByPk<'a, T> {
     fn name() -> String {
         format!("{}_by_pk", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + DeserializeOwned> Display for QueryByPk<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + DeserializeOwned> Render for QueryByPk<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
         Builder::new(Self::name(), &self.returning)
-            .pk(&Flattened(&self.pk))
-            .write(f)
+            .alias(self.alias)
+            .pk(&PkArgs::<T>(&self.pk))
+            .render(context)
     }
 }
@@ -0,0 +1,59 @@
+use crate::{Builder, Conditions, Context, Cursor, CursorOrdering, Field, Fields, Hasura, Render, Result, Subscribable};
+use serde::de::DeserializeOwned;
+
+pub struct Stream<'a, T: Hasura> {
+    pub batch_size: u64,
+    pub cursor: Cursor<'a, T>,
+    pub conditions: Option<Conditions<'a, T>>,
+    pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
+}
+
+impl<'a, T: Hasura> Stream<'a, T> {
+    pub fn new(batch_size: u64, cursor: Field<'a, T>, initial_value: impl serde::Serialize) -> Self {
+        Self {
+            batch_size,
+            cursor: Cursor::new(cursor, initial_value),
+            conditions: None,
+            returning: T::all(),
+            alias: None,
+        }
+    }
+    pub fn ordering(mut self, ordering: CursorOrdering) -> Self {
+        self.cursor = self.cursor.ordering(ordering);
+        self
+    }
+    pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
+        self.conditions = Some(conditions);
+        self
+    }
+    pub fn returning(mut self, returning: Fields<'a, T>) -> Self {
+        self.returning = returning;
+        self
+    }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura + DeserializeOwned> Subscribable<T> for Stream<'a, T> {
+    type Out = Vec<T>;
+    fn name() -> String {
+        format!("{}_stream", T::table())
+    }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
+}
+
+impl<'a, T: Hasura + DeserializeOwned> Render for Stream<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        Builder::new(Self::name(), &self.returning)
+            .alias(self.alias)
+            .param("batch_size", &self.batch_size)
+            .input("cursor", &self.cursor)
+            .maybe_input("where", &self.conditions.as_ref().map(Conditions::variable))
+            .render(context)
+    }
+}
@@ -1,28 +1,61 @@
-use crate::{Braced, Builder, Conditions, Fields, Hasura, Mutation, Serialized};
+use crate::{Builder, Conditions, Context, Error, Field, Fields, Hasura, Mutation, MutationResponse, Operators, Render, Result, Set, Variable};
 use serde::de::DeserializeOwned;
-use std::fmt::{Display, Formatter};
 use serde::Serialize;
 
 pub struct Update<'a, T: Hasura> {
-    pub set: &'a T,
+    pub set: Set<'a, T>,
+    pub operators: Operators<'a, T>,
     pub conditions: Conditions<'a, T>,
+    pub all_rows: bool,
     pub affected_rows: bool,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
 impl<'a, T: Hasura + Serialize> Update<'a, T> {
-    pub fn new(set: &'a T) -> Self {
+    pub fn new(set: impl Into<Set<'a, T>>) -> Self {
         Self {
-            set,
+            set: set.into(),
+            operators: Operators::default(),
             conditions: Conditions::None,
+            all_rows: false,
             affected_rows: false,
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn conditions(mut self, conditions: Conditions<'a, T>) -> Self {
         self.conditions = conditions;
         self
     }
+    pub fn all_rows(mut self) -> Self {
+        self.all_rows = true;
+        self
+    }
+    pub fn inc(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.inc.push(field, value);
+        self
+    }
+    pub fn append(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.append.push(field, value);
+        self
+    }
+    pub fn prepend(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.prepend.push(field, value);
+        self
+    }
+    pub fn delete_key(mut self, field: Field<'a, T>, key: &str) -> Self {
+        self.operators.delete_key.push(field, key);
+        self
+    }
+    pub fn delete_elem(mut self, field: Field<'a, T>, index: i64) -> Self {
+        self.operators.delete_elem.push(field, index);
+        self
+    }
+    pub fn delete_at_path(mut self, field: Field<'a, T>, path: Vec<&str>) -> Self {
+        self.operators.delete_at_path.push(field, path);
+        self
+    }
     pub fn affected_rows(mut self, affected_rows: bool) -> Self {
         self.affected_rows = affected_rows;
         self
@@ -31,21 +64,44 @@ impl<'a, T: Hasura + Serialize> Update<'a, T> {
         self.returning = returning;
         self
     }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura + Serialize> Default for Update<'a, T> {
+    fn default() -> Self {
+        Self::new(Set::new())
+    }
 }
 
 impl<'a, T: Hasura + Serialize + DeserializeOwned> Mutation<T> for Update<'a, T> {
-    type Out = Vec<T>;
+    type Out = MutationResponse<T>;
     fn name() -> String {
         format!("update_{}", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + Serialize + DeserializeOwned> Display for Update<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + Serialize + DeserializeOwned> Render for Update<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        if !self.all_rows && self.conditions.is_unconditional() {
+            return Err(Error::Unconditional(Self::name()));
+        }
+
+        let set = (!self.set.is_empty()).then(|| Variable::new(format!("{}_set_input!", T::table()), &self.set, || self.set.value()));
+        let operators = self.operators.params();
+
         Builder::new(Self::name(), &self.returning)
-            .param("_set", &Serialized(&self.set))
-            .param("where", &Braced(&self.conditions))
+            .alias(self.alias)
+            .maybe_input("_set", &set)
+            .inputs(&operators)
+            .input("where", &self.conditions.variable())
+            .affected(self.affected_rows)
             .explicit(true)
-            .write(f)
+            .render(context)
     }
 }
@@ -1,27 +1,58 @@
-use std::fmt::{Display, Formatter};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use crate::{Builder, Fields, Hasura, Mutation};
+use crate::{Builder, Context, Field, Fields, Hasura, Mutation, Operators, Render, Result, Set, Variable};
 use crate::Serialized;
 
 pub struct UpdateByPk<'a, T: Hasura> {
     pub pk: T::Pk,
-    pub set: &'a T,
+    pub set: Set<'a, T>,
+    pub operators: Operators<'a, T>,
     pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
 }
 
-impl<'a, T: Hasura> UpdateByPk<'a, T> {
-    pub fn new(pk: T::Pk, set: &'a T) -> Self {
+impl<'a, T: Hasura + Serialize> UpdateByPk<'a, T> {
+    pub fn new(pk: T::Pk, set: impl Into<Set<'a, T>>) -> Self {
         Self {
             pk,
-            set,
+            set: set.into(),
+            operators: Operators::default(),
             returning: T::all(),
+            alias: None,
         }
     }
     pub fn returning(mut self, returning: Fields<'a, T>) -> Self {
         self.returning = returning;
         self
     }
+    pub fn inc(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.inc.push(field, value);
+        self
+    }
+    pub fn append(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.append.push(field, value);
+        self
+    }
+    pub fn prepend(mut self, field: Field<'a, T>, value: impl Serialize) -> Self {
+        self.operators.prepend.push(field, value);
+        self
+    }
+    pub fn delete_key(mut self, field: Field<'a, T>, key: &str) -> Self {
+        self.operators.delete_key.push(field, key);
+        self
+    }
+    pub fn delete_elem(mut self, field: Field<'a, T>, index: i64) -> Self {
+        self.operators.delete_elem.push(field, index);
+        self
+    }
+    pub fn delete_at_path(mut self, field: Field<'a, T>, path: Vec<&str>) -> Self {
+        self.operators.delete_at_path.push(field, path);
+        self
+    }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
 }
 
 impl<'a, T: Hasura + Serialize + DeserializeOwned> Mutation<T> for UpdateByPk<'a, T> {
@@ -29,13 +60,22 @@ impl<'a, T: Hasura + Serialize + DeserializeOwned> Mutation<T> for UpdateByPk<'a
     fn name() -> String {
         format!("update_{}_by_pk", T::table())
     }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
 }
 
-impl<'a, T: Hasura + Serialize + DeserializeOwned> Display for UpdateByPk<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<'a, T: Hasura + Serialize + DeserializeOwned> Render for UpdateByPk<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let pk = Variable::new(format!("{}_pk_columns_input!", T::table()), Serialized(&self.pk), || Ok(serde_json::to_value(&self.pk)?));
+        let set = (!self.set.is_empty()).then(|| Variable::new(format!("{}_set_input!", T::table()), &self.set, || self.set.value()));
+        let operators = self.operators.params();
+
         Builder::new(Self::name(), &self.returning)
-            .param("pk_columns", &Serialized(&self.pk))
-            .param("_set", &Serialized(&self.set))
-            .write(f)
+            .alias(self.alias)
+            .input("pk_columns", &pk)
+            .maybe_input("_set", &set)
+            .inputs(&operators)
+            .render(context)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,109 @@
+use crate::{Braced, Builder, Conditions, Context, Error, Fields, Hasura, Mutation, MutationResponse, Operators, Render, Result, Set, Variable};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub struct UpdateMany<'a, T: Hasura> {
+    pub updates: Vec<(Conditions<'a, T>, Set<'a, T>, Operators<'a, T>)>,
+    pub all_rows: bool,
+    pub returning: Fields<'a, T>,
+    pub alias: Option<&'a str>,
+}
+
+impl<'a, T: Hasura + Serialize> UpdateMany<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            updates: vec![],
+            all_rows: false,
+            returning: T::all(),
+            alias: None,
+        }
+    }
+    pub fn update(self, conditions: Conditions<'a, T>, set: impl Into<Set<'a, T>>) -> Self {
+        self.update_with(conditions, set, Operators::new())
+    }
+    pub fn update_with(mut self, conditions: Conditions<'a, T>, set: impl Into<Set<'a, T>>, operators: Operators<'a, T>) -> Self {
+        self.updates.push((conditions, set.into(), operators));
+        self
+    }
+    pub fn all_rows(mut self) -> Self {
+        self.all_rows = true;
+        self
+    }
+    pub fn returning(mut self, returning: Fields<'a, T>) -> Self {
+        self.returning = returning;
+        self
+    }
+
+    fn value(&self) -> Result<Value> {
+        let updates = self.updates.iter().map(|(conditions, set, operators)| {
+            let mut update = Map::new();
+            update.insert("where".to_string(), conditions.value()?);
+            if !set.is_empty() {
+                update.insert("_set".to_string(), set.value()?);
+            }
+            for (key, _, columns) in operators.present() {
+                update.insert(key.to_string(), columns.value()?);
+            }
+            Ok(Value::Object(update))
+        });
+        Ok(Value::Array(updates.collect::<Result<_>>()?))
+    }
+    pub fn alias(mut self, alias: &'a str) -> Self {
+        self.alias = Some(alias);
+        self
+    }
+}
+
+impl<'a, T: Hasura + Serialize> Default for UpdateMany<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Updates<'b, 'a, T: Hasura>(&'b [(Conditions<'a, T>, Set<'a, T>, Operators<'a, T>)]);
+
+impl<'b, 'a, T: Hasura + Serialize> Render for Updates<'b, 'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let updates = self.0.iter().map(|(conditions, set, operators)| {
+            let mut update = vec![format!("where: {}", Braced(conditions).render(context)?)];
+            if !set.is_empty() {
+                update.push(format!("_set: {}", set.render(context)?));
+            }
+            for (key, _, columns) in operators.present() {
+                update.push(format!("{key}: {}", columns.render(context)?));
+            }
+            Ok(format!("{{{}}}", update.join(", ")))
+        });
+        Ok(format!("[{}]", updates.collect::<Result<Vec<_>>>()?.join(", ")))
+    }
+}
+
+impl<'a, T: Hasura + Serialize + DeserializeOwned> Mutation<T> for UpdateMany<'a, T> {
+    type Out = Vec<MutationResponse<T>>;
+    fn name() -> String {
+        format!("update_{}_many", T::table())
+    }
+    fn key(&self) -> String {
+        self.alias.map_or_else(Self::name, str::to_string)
+    }
+}
+
+impl<'a, T: Hasura + Serialize + DeserializeOwned> Render for UpdateMany<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let unconditional = self.updates.iter().any(|(conditions, _, _)| conditions.is_unconditional());
+        if !self.all_rows && unconditional {
+            return Err(Error::Unconditional(Self::name()));
+        }
+
+        let ty = format!("[{}_updates!]!", T::table());
+        let updates = Variable::new(ty, Updates(&self.updates), || self.value());
+
+        Builder::new(Self::name(), &self.returning)
+            .alias(self.alias)
+            .input("updates", &updates)
+            .affected(true)
+            .explicit(true)
+            .render(context)
+    }
+}
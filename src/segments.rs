@@ -1,40 +1,134 @@
 use crate::error::{Error, Result};
 use crate::traits::{Mutation, Queryable};
-use crate::{Fetcher, Hasura};
+use crate::{Context, Fetcher, Hasura, Mutations, Queries};
+#[cfg(feature = "subscriptions")]
+use crate::{Subscribable, Subscriber, Subscriptions};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 
-fn decode<O: DeserializeOwned>(value: &Value, operation: &str, returning: bool) -> Result<O> {
-    let mut entry = value.get(operation).ok_or(Error::Empty)?;
+pub(crate) fn decode<O: DeserializeOwned>(value: &Value, operation: &str) -> Result<O> {
+    let entry = value.get(operation).ok_or(Error::Empty)?;
+    Ok(serde_json::from_value(entry.clone())?)
+}
 
-    if let (true, Some(new)) = (returning, entry.get("returning")) {
-        entry = new
-    }
+fn dec_query<P: Hasura, T: Queryable<P>>(val: &Value, key: &str) -> Result<T::Out> {
+    decode(val, key)
+}
 
-    Ok(serde_json::from_value(entry.clone())?)
+fn dec_mut<P: Hasura, T: Mutation<P>>(val: &Value, key: &str) -> Result<T::Out> {
+    decode(val, key)
 }
 
-fn dec_query<P: Hasura, T: Queryable<P>>(val: &Value) -> Result<T::Out> {
-    decode(val, &T::name(), false)
+#[cfg(feature = "subscriptions")]
+fn dec_sub<P: Hasura, T: Subscribable<P>>(val: &Value, key: &str) -> Result<T::Out> {
+    decode(val, key)
 }
 
-fn dec_mut<P: Hasura, T: Mutation<P>>(val: &Value) -> Result<T::Out> {
-    decode(val, &T::name(), true)
+fn checked<O>(
+    operation: &str,
+    roots: Result<String>,
+    context: Context,
+    extract: impl FnOnce(Value) -> Result<O> + 'static,
+) -> Fetcher<O> {
+    match roots {
+        Ok(roots) => Fetcher::new(operation, roots, context, extract),
+        Err(error) => Fetcher::failed(error),
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+fn subscribed<O>(
+    roots: Result<String>,
+    context: Context,
+    extract: impl Fn(Value) -> Result<O> + 'static,
+) -> Subscriber<O> {
+    match roots {
+        Ok(roots) => Subscriber::new("subscription", roots, context, extract),
+        Err(error) => Subscriber::failed(error),
+    }
+}
+
+impl Context {
+    pub fn query1<P1: Hasura, T1: Queryable<P1>>(mut self, t1: &T1) -> Fetcher<T1::Out> {
+        let k1 = t1.key();
+        let extract = move |val: Value| dec_query::<_, T1>(&val, &k1);
+        let roots = t1.render(&mut self);
+        checked("query", roots, self, extract)
+    }
+
+    pub fn mutation1<P1: Hasura, T1: Mutation<P1>>(mut self, t1: &T1) -> Fetcher<T1::Out> {
+        let k1 = t1.key();
+        let extract = move |val: Value| dec_mut::<_, T1>(&val, &k1);
+        let roots = t1.render(&mut self);
+        checked("mutation", roots, self, extract)
+    }
+
+    #[cfg(feature = "subscriptions")]
+    pub fn subscription1<P1: Hasura, T1: Subscribable<P1>>(mut self, t1: &T1) -> Subscriber<T1::Out> {
+        let k1 = t1.key();
+        let extract = move |val: Value| dec_sub::<_, T1>(&val, &k1);
+        let roots = t1.render(&mut self);
+        subscribed(roots, self, extract)
+    }
+
+    pub fn query<P, Q: Queries<P>>(mut self, queries: &Q) -> Fetcher<Q::Out> {
+        let keys = queries.keys();
+        let extract = move |val: Value| Q::decode(&val, &keys);
+        let roots = queries.roots(&mut self);
+        checked("query", roots, self, extract)
+    }
+
+    pub fn mutation<P, M: Mutations<P>>(mut self, mutations: &M) -> Fetcher<M::Out> {
+        let keys = mutations.keys();
+        let extract = move |val: Value| M::decode(&val, &keys);
+        let roots = mutations.roots(&mut self);
+        checked("mutation", roots, self, extract)
+    }
+
+    #[cfg(feature = "subscriptions")]
+    pub fn subscription<P, S: Subscriptions<P>>(mut self, subscriptions: &S) -> Subscriber<S::Out> {
+        let keys = subscriptions.keys();
+        let extract = move |val: Value| S::decode(&val, &keys);
+        let roots = subscriptions.roots(&mut self);
+        subscribed(roots, self, extract)
+    }
 }
 
 pub fn query1<P1: Hasura, T1: Queryable<P1>>(t1: &T1) -> Fetcher<T1::Out> {
-    let extract = move |val: Value| dec_query::<_, T1>(&val);
-    Fetcher::new(format!("query {{ {t1} }}"), extract)
+    Context::inline().query1(t1)
+}
+
+pub fn mutation1<P1: Hasura, T1: Mutation<P1>>(t1: &T1) -> Fetcher<T1::Out> {
+    Context::inline().mutation1(t1)
 }
 
+#[cfg(feature = "subscriptions")]
+pub fn subscription1<P1: Hasura, T1: Subscribable<P1>>(t1: &T1) -> Subscriber<T1::Out> {
+    Context::inline().subscription1(t1)
+}
+
+pub fn query<P, Q: Queries<P>>(queries: &Q) -> Fetcher<Q::Out> {
+    Context::inline().query(queries)
+}
+
+pub fn mutation<P, M: Mutations<P>>(mutations: &M) -> Fetcher<M::Out> {
+    Context::inline().mutation(mutations)
+}
+
+#[cfg(feature = "subscriptions")]
+pub fn subscription<P, S: Subscriptions<P>>(subscriptions: &S) -> Subscriber<S::Out> {
+    Context::inline().subscription(subscriptions)
+}
+
+#[deprecated(note = "use `query(&(t1, t2))` or the `query!` macro")]
 pub fn query2<P1: Hasura, P2: Hasura, T1: Queryable<P1>, T2: Queryable<P2>>(
     t1: &T1,
     t2: &T2,
 ) -> Fetcher<(T1::Out, T2::Out)> {
-    let extract = |val| Ok((dec_query::<_, T1>(&val)?, dec_query::<_, T2>(&val)?));
-    Fetcher::new(format!("query {{ {t1} {t2} }}"), extract)
+    query(&(t1, t2))
 }
 
+#[deprecated(note = "use `query(&(t1, t2, t3))` or the `query!` macro")]
 pub fn query3<
     P1: Hasura,
     P2: Hasura,
@@ -47,29 +141,18 @@ pub fn query3<
     t2: &T2,
     t3: &T3,
 ) -> Fetcher<(T1::Out, T2::Out, T3::Out)> {
-    let extract = |val| {
-        Ok((
-            dec_query::<_, T1>(&val)?,
-            dec_query::<_, T2>(&val)?,
-            dec_query::<_, T3>(&val)?,
-        ))
-    };
-    Fetcher::new(format!("query {{ {t1} {t2} {t3} }}"), extract)
-}
-
-pub fn mutation1<P1: Hasura, T1: Mutation<P1>>(t1: &T1) -> Fetcher<T1::Out> {
-    let extract = move |val: Value| dec_mut::<_, T1>(&val);
-    Fetcher::new(format!("mutation {{ {t1} }}"), extract)
+    query(&(t1, t2, t3))
 }
 
+#[deprecated(note = "use `mutation(&(t1, t2))` or the `mutation!` macro")]
 pub fn mutation2<P1: Hasura, P2: Hasura, T1: Mutation<P1>, T2: Mutation<P2>>(
     t1: &T1,
     t2: &T2,
 ) -> Fetcher<(T1::Out, T2::Out)> {
-    let extract = |val| Ok((dec_mut::<_, T1>(&val)?, dec_mut::<_, T2>(&val)?));
-    Fetcher::new(format!("mutation {{ {t1} {t2} }}"), extract)
+    mutation(&(t1, t2))
 }
 
+#[deprecated(note = "use `mutation(&(t1, t2, t3))` or the `mutation!` macro")]
 pub fn mutation3<
     P1: Hasura,
     P2: Hasura,
@@ -82,12 +165,31 @@ pub fn mutation3<
     t2: &T2,
     t3: &T3,
 ) -> Fetcher<(T1::Out, T2::Out, T3::Out)> {
-    let extract = |val| {
-        Ok((
-            dec_mut::<_, T1>(&val)?,
-            dec_mut::<_, T2>(&val)?,
-            dec_mut::<_, T3>(&val)?,
-        ))
-    };
-    Fetcher::new(format!("mutation {{ {t1} {t2} {t3} }}"), extract)
+    mutation(&(t1, t2, t3))
+}
+
+#[cfg(feature = "subscriptions")]
+#[deprecated(note = "use `subscription(&(t1, t2))` or the `subscription!` macro")]
+pub fn subscription2<P1: Hasura, P2: Hasura, T1: Subscribable<P1>, T2: Subscribable<P2>>(
+    t1: &T1,
+    t2: &T2,
+) -> Subscriber<(T1::Out, T2::Out)> {
+    subscription(&(t1, t2))
+}
+
+#[cfg(feature = "subscriptions")]
+#[deprecated(note = "use `subscription(&(t1, t2, t3))` or the `subscription!` macro")]
+pub fn subscription3<
+    P1: Hasura,
+    P2: Hasura,
+    P3: Hasura,
+    T1: Subscribable<P1>,
+    T2: Subscribable<P2>,
+    T3: Subscribable<P3>,
+>(
+    t1: &T1,
+    t2: &T2,
+    t3: &T3,
+) -> Subscriber<(T1::Out, T2::Out, T3::Out)> {
+    subscription(&(t1, t2, t3))
 }
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use futures::{SinkExt, Stream, StreamExt};
+use serde_json::{json, Map, Value};
+use tokio_tungstenite_wasm::{Message, WebSocketStream};
+
+use crate::fetcher::{GraphqlError, Response};
+use crate::{Context, Error, Result};
+
+const PROTOCOL: &str = "graphql-transport-ws";
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Incoming {
+    ConnectionAck,
+    Ping,
+    Pong,
+    Next { payload: Response },
+    Error { payload: Vec<GraphqlError> },
+    Complete,
+}
+
+async fn receive(ws: &mut WebSocketStream) -> Option<Result<Incoming>> {
+    loop {
+        let text = match ws.next().await? {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Binary(_)) => continue,
+            Ok(Message::Close(_)) => return None,
+            Err(err) => return Some(Err(err.into())),
+        };
+
+        let incoming = match serde_json::from_str(&text) {
+            Ok(Incoming::Ping) => {
+                let pong = json!({"type": "pong"}).to_string();
+                match ws.send(Message::text(pong)).await {
+                    Ok(()) => continue,
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Ok(Incoming::Pong) => continue,
+            other => other.map_err(Error::from),
+        };
+        return Some(incoming);
+    }
+}
+
+async fn connect(url: &str, headers: HashMap<String, String>) -> Result<WebSocketStream> {
+    let url = match url.strip_prefix("http") {
+        Some(rest) => format!("ws{rest}"),
+        None => url.to_string(),
+    };
+
+    let mut ws = tokio_tungstenite_wasm::connect_with_protocols(url, &[PROTOCOL]).await?;
+
+    let init = json!({"type": "connection_init", "payload": {"headers": headers}});
+    ws.send(Message::text(init.to_string())).await?;
+
+    match receive(&mut ws).await {
+        Some(Ok(Incoming::ConnectionAck)) => Ok(ws),
+        Some(Ok(other)) => Err(Error::Protocol(format!("expected connection_ack, got {other:?}"))),
+        Some(Err(err)) => Err(err),
+        None => Err(Error::Protocol("connection closed before connection_ack".to_string())),
+    }
+}
+
+pub struct Subscriber<O> {
+    pub query: String,
+    pub variables: Map<String, Value>,
+    pub extract: Box<dyn Fn(Value) -> Result<O>>,
+    pub headers: HashMap<String, String>,
+    pub error: Option<Error>,
+}
+
+impl<O> Subscriber<O> {
+    pub fn new<Fn: std::ops::Fn(Value) -> Result<O> + 'static>(operation: &str, roots: String, context: Context, extract: Fn) -> Self {
+        let (query, variables) = context.operation(operation, roots);
+        Self {
+            query,
+            variables,
+            extract: Box::new(extract),
+            headers: HashMap::new(),
+            error: None,
+        }
+    }
+
+    pub(crate) fn failed(error: Error) -> Self {
+        Self {
+            query: String::new(),
+            variables: Map::new(),
+            extract: Box::new(|_| Err(Error::Empty)),
+            headers: HashMap::new(),
+            error: Some(error),
+        }
+    }
+
+    pub fn header(mut self, key: impl ToString, value: impl ToString) -> Self {
+        self.headers.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn admin(self, admin: impl Into<Option<String>>) -> Self {
+        match admin.into() {
+            Some(admin) => self.header("x-hasura-admin-secret", admin),
+            None => self,
+        }
+    }
+
+    pub fn token(self, token: impl Into<Option<String>>) -> Self {
+        match token.into() {
+            Some(token) => self.header("authorization", token),
+            None => self,
+        }
+    }
+
+    pub async fn subscribe(self, url: &str) -> Result<impl Stream<Item = Result<O>>> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let mut ws = connect(url, self.headers).await?;
+
+        let subscribe = json!({"id": "1", "type": "subscribe", "payload": {"query": self.query, "variables": self.variables}});
+        ws.send(Message::text(subscribe.to_string())).await?;
+
+        let stream = futures::stream::unfold(Some((ws, self.extract)), |state| async move {
+            let (mut ws, extract) = state?;
+
+            let item = match receive(&mut ws).await? {
+                Ok(Incoming::Next { payload }) => payload.data().and_then(&extract),
+                Ok(Incoming::Error { payload }) => return Some((Err(Error::Hasura(payload)), None)),
+                Ok(Incoming::Complete) => return None,
+                Ok(other) => Err(Error::Protocol(format!("unexpected message {other:?}"))),
+                Err(err) => return Some((Err(err), None)),
+            };
+            Some((item, Some((ws, extract))))
+        });
+
+        Ok(stream)
+    }
+}
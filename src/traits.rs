@@ -1,21 +1,62 @@
-use crate::{Field, Fields};
+use crate::{Context, Field, Fields, Result};
 use serde::de::DeserializeOwned;
 use std::fmt::Display;
 
 pub trait Hasura {
     type Pk: serde::Serialize;
+    type Column: Display + Clone;
+    type Constraint: Display + Clone;
 
     fn table<'a>() -> &'a str;
+    fn pk_types<'a>() -> Vec<(&'a str, &'a str)>;
     fn all<'a>() -> Fields<'a, Self> where Self: Sized;
     fn except<'a>(fields: &[Field<'a, Self>]) -> Fields<'a, Self> where Self: Sized;
 }
 
-pub trait Queryable<P: Hasura>: Display {
+/// Renders a part of an operation, registering its values in the context when it collects variables.
+pub trait Render {
+    fn render(&self, context: &mut Context) -> Result<String>;
+
+    /// Renders with every value inlined as a GraphQL literal.
+    fn to_graphql(&self) -> Result<String> {
+        self.render(&mut Context::inline())
+    }
+}
+
+impl<T: Render + ?Sized> Render for &T {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        (**self).render(context)
+    }
+}
+
+impl<T: Render + ?Sized> Render for Box<T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        (**self).render(context)
+    }
+}
+
+pub trait Queryable<P: Hasura>: Render {
+    type Out: DeserializeOwned;
+    fn name() -> String;
+    fn key(&self) -> String {
+        Self::name()
+    }
+}
+
+pub trait Mutation<P: Hasura>: Render {
     type Out: DeserializeOwned;
     fn name() -> String;
+    fn key(&self) -> String {
+        Self::name()
+    }
 }
 
-pub trait Mutation<P: Hasura>: Display {
+/// A root of a subscription: any query, or a `_stream` which only exists on the subscription root.
+#[cfg(feature = "subscriptions")]
+pub trait Subscribable<P: Hasura>: Render {
     type Out: DeserializeOwned;
     fn name() -> String;
+    fn key(&self) -> String {
+        Self::name()
+    }
 }
@@ -1,57 +1,52 @@
-use crate::{Fields, Hasura};
 use itertools::Itertools;
-use std::fmt::{Display, Formatter, Result};
+use std::fmt::{Display, Formatter};
+use crate::{Context, Render, Result};
 
 pub trait Maybe<T: Display> {
     fn maybe(&self) -> Option<&T>;
 }
 
-pub struct Braced<'a, T: Display>(pub &'a T);
+pub struct Braced<'a, T: Render + ?Sized>(pub &'a T);
 pub struct Serialized<'a, T: serde::Serialize>(pub &'a T);
 pub struct Flattened<'a, T: serde::Serialize>(pub &'a T);
 
-pub struct Separated<'a, T: Display>(pub &'a [T]);
+pub struct Listed<'a, T: Display>(pub &'a [T]);
 pub struct Separalized<'a, T: serde::Serialize>(pub &'a [T]);
 
-impl<'a, T: Display> Display for Braced<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{{ {} }}", self.0)
+impl<'a, T: Render + ?Sized> Render for Braced<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        Ok(format!("{{ {} }}", self.0.render(context)?))
     }
 }
 
-impl<'a, T: serde::Serialize> Display for Serialized<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", crate::to_string(self.0, true).unwrap())
+impl<'a, T: serde::Serialize> Render for Serialized<'a, T> {
+    fn render(&self, _: &mut Context) -> Result<String> {
+        Ok(crate::to_string(self.0, true)?)
     }
 }
 
-impl<'a, T: serde::Serialize> Display for Flattened<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", crate::to_string(self.0, false).unwrap())
+impl<'a, T: serde::Serialize> Render for Flattened<'a, T> {
+    fn render(&self, _: &mut Context) -> Result<String> {
+        Ok(crate::to_string(self.0, false)?)
     }
 }
 
-impl<'a, T: Display> Display for Separated<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.0.iter().join(", "))
+impl<'a, T: Display> Display for Listed<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", self.0.iter().join(", "))
     }
 }
 
-impl<'a, T: Display> Maybe<Self> for Separated<'a, T> {
+impl<'a, T: Display> Maybe<Self> for Listed<'a, T> {
     fn maybe(&self) -> Option<&Self> {
-        (!self.0.is_empty()).then(|| self)
+        (!self.0.is_empty()).then_some(self)
     }
 }
 
-impl<'a, T: serde::Serialize> Display for Separalized<'a, T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "[{}]", self.0.iter().map(Serialized).join(", "))
-    }
-}
-
-impl<'a, T: serde::Serialize> Maybe<Self> for Separalized<'a, T> {
-    fn maybe(&self) -> Option<&Self> {
-        (!self.0.is_empty()).then(|| self)
+impl<'a, T: serde::Serialize> Render for Separalized<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        let values = self.0.iter().map(|value| Serialized(value).render(context)).collect::<Result<Vec<_>>>()?;
+        Ok(format!("[{}]", values.join(", ")))
     }
 }
 
@@ -61,21 +56,28 @@ impl<T: Display> Maybe<T> for Option<T> {
     }
 }
 
-pub struct Builder<'a, T: Hasura> {
+enum Param<'a> {
+    Plain(&'a dyn Display),
+    Input(&'a dyn Render),
+}
+
+pub struct Builder<'a> {
     name: String,
-    returning: &'a Fields<'a, T>,
+    alias: Option<&'a str>,
+    returning: &'a dyn Render,
 
-    params: Vec<(&'a str, &'a dyn Display)>,
-    pk: Option<&'a dyn Display>,
+    params: Vec<(&'a str, Param<'a>)>,
+    pk: Option<&'a dyn Render>,
 
     affected: bool,
     explicit: bool,
 }
 
-impl<'a, T: Hasura> Builder<'a, T> {
-    pub fn new(name: String, returning: &'a Fields<'a, T>) -> Self {
+impl<'a> Builder<'a> {
+    pub fn new(name: String, returning: &'a dyn Render) -> Self {
         Self {
             name,
+            alias: None,
             returning,
             params: vec![],
             pk: None,
@@ -84,8 +86,12 @@ impl<'a, T: Hasura> Builder<'a, T> {
         }
     }
 
+    pub fn alias(mut self, alias: Option<&'a str>) -> Self {
+        self.alias = alias;
+        self
+    }
     pub fn param<D: Display>(mut self, key: &'a str, value: &'a D) -> Self {
-        self.params.push((key, value));
+        self.params.push((key, Param::Plain(value)));
         self
     }
     pub fn maybe<I: Display + 'a, D: Maybe<I>>(self, key: &'a str, value: &'a D) -> Self {
@@ -94,11 +100,27 @@ impl<'a, T: Hasura> Builder<'a, T> {
             None => self,
         }
     }
+    pub fn input<R: Render>(mut self, key: &'a str, value: &'a R) -> Self {
+        self.params.push((key, Param::Input(value)));
+        self
+    }
+    pub fn inputs<R: Render>(mut self, inputs: &'a [(&'a str, R)]) -> Self {
+        for (key, value) in inputs {
+            self.params.push((key, Param::Input(value)));
+        }
+        self
+    }
+    pub fn maybe_input<R: Render>(self, key: &'a str, value: &'a Option<R>) -> Self {
+        match value {
+            Some(value) => self.input(key, value),
+            None => self,
+        }
+    }
     pub fn affected(mut self, affected: bool) -> Self {
         self.affected = affected;
         self
     }
-    pub fn pk(mut self, pk: &'a impl Display) -> Self {
+    pub fn pk(mut self, pk: &'a impl Render) -> Self {
         self.pk = Some(pk);
         self
     }
@@ -107,26 +129,42 @@ impl<'a, T: Hasura> Builder<'a, T> {
         self
     }
 
-    pub fn write(self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.name)?;
+    pub fn render(self, context: &mut Context) -> Result<String> {
+        let mut output = String::new();
+        if let Some(alias) = self.alias {
+            output += &format!("{alias}: ");
+        }
+        output += &self.name;
 
+        let pk = self.pk.map(|pk| pk.render(context)).transpose()?;
         let params = self
             .params
             .iter()
-            .map(|(k, v)| format!("{}: {}", k, v))
+            .map(|(k, v)| match v {
+                Param::Plain(value) => Ok(format!("{k}: {value}")),
+                Param::Input(value) => Ok(format!("{k}: {}", value.render(context)?)),
+            })
+            .collect::<Result<Vec<_>>>()?
             .join(", ");
 
-        match (params, self.pk) {
-            (params, Some(pk)) => write!(f, "({pk}, {params})")?,
+        match (params, pk) {
+            (params, Some(pk)) => output += &format!("({pk}, {params})"),
             (params, None) if params.is_empty() => (),
-            (params, None) => write!(f, "({params})")?,
+            (params, None) => output += &format!("({params})"),
         };
 
-        match (self.affected, self.explicit) {
-            (false, false) => write!(f, " {{ {} }}", self.returning),
-            (false, true) => write!(f, " {{ returning {{ {} }} }}", self.returning),
-            (true, false) => write!(f, " {{ {} affected_rows }}", self.returning),
-            (true, true) => write!(f, " {{ returning {{ {} }} affected_rows }}", self.returning),
+        let returning = self.returning.render(context)?;
+        let mut selection = match (self.explicit, returning.is_empty()) {
+            (true, true) => vec![],
+            (true, false) => vec![format!("returning {{ {returning} }}")],
+            (false, _) => vec![returning],
+        };
+
+        // a selection set can't be empty, so fall back on affected_rows
+        if self.affected || selection.is_empty() {
+            selection.push("affected_rows".to_string());
         }
+
+        Ok(output + &format!(" {{ {} }}", selection.join(" ")))
     }
 }
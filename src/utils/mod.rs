@@ -1,2 +1,3 @@
 pub mod builder;
-pub mod serializer;
\ No newline at end of file
+pub mod serializer;
+pub mod variables;
@@ -1,40 +1,20 @@
-use serde::{de, ser, Serialize};
-use std;
-use std::fmt::{self, Display};
+use serde::{ser, Serialize};
+use std::fmt::{self, Display, Write};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-// This is a bare-bones implementation. A real library would provide additional
-// information in its error type, for example the line and column at which the
-// error occurred, the byte offset into the input, or the current key being
-// processed.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Error {
-    // One or more variants that can be created by data structures through the
-    // `ser::Error` and `de::Error` traits. For example the Serialize impl for
-    // Mutex<T> might return an error because the mutex is poisoned, or the
-    // Deserialize impl for a struct may return an error because a required
-    // field is missing.
+    /// Raised by a `Serialize` implementation through `ser::Error::custom`.
     Message(String),
-
-    // Zero or more variants that can be created directly by the Serializer and
-    // Deserializer without going through `ser::Error` and `de::Error`. These
-    // are specific to the format, in this case JSON.
-    Eof,
-    Syntax,
-    ExpectedBoolean,
-    ExpectedInteger,
-    ExpectedString,
-    ExpectedNull,
-    ExpectedArray,
-    ExpectedArrayComma,
-    ExpectedArrayEnd,
-    ExpectedMap,
-    ExpectedMapColon,
-    ExpectedMapComma,
-    ExpectedMapEnd,
-    ExpectedEnum,
-    TrailingCharacters,
+    /// GraphQL has no literal for NaN or infinities.
+    NonFiniteFloat(f64),
+    /// Object keys and enum values must be GraphQL names (`[_A-Za-z][_0-9A-Za-z]*`).
+    InvalidName(String),
+    /// `true`, `false` and `null` cannot be used as enum values.
+    ReservedEnumValue(String),
+    /// Object keys must serialize to strings.
+    KeyMustBeString,
 }
 
 impl ser::Error for Error {
@@ -43,43 +23,47 @@ impl ser::Error for Error {
     }
 }
 
-impl de::Error for Error {
-    fn custom<T: Display>(msg: T) -> Self {
-        Error::Message(msg.to_string())
-    }
-}
-
 impl Display for Error {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::Message(msg) => formatter.write_str(msg),
-            Error::Eof => formatter.write_str("unexpected end of input"),
-            _ => formatter.write_str("todo"),
+            Error::Message(msg) => f.write_str(msg),
+            Error::NonFiniteFloat(v) => write!(f, "cannot represent {v} as a GraphQL Float"),
+            Error::InvalidName(name) => write!(f, "{name:?} is not a valid GraphQL name"),
+            Error::ReservedEnumValue(name) => write!(f, "{name:?} cannot be used as a GraphQL enum value"),
+            Error::KeyMustBeString => f.write_str("GraphQL object keys must be strings"),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let first = chars.next().is_some_and(|c| c == '_' || c.is_ascii_alphabetic());
+    first && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+fn name(name: &str) -> Result<&str> {
+    match is_name(name) {
+        true => Ok(name),
+        false => Err(Error::InvalidName(name.to_string())),
+    }
+}
+
+/// Serializes values as GraphQL input literals: object keys and enum values are bare names,
+/// strings are escaped and numbers are unquoted.
+#[derive(Default)]
 pub struct Serializer {
-    // This string starts empty and JSON is appended as values are serialized.
     output: String,
-    skip_quotes: bool,
 }
 
-// By convention, the public API of a Serde serializer is one or more `to_abc`
-// functions such as `to_string`, `to_bytes`, or `to_writer` depending on what
-// Rust types the serializer is able to produce as output.
-//
-// This basic serializer supports only `to_string`.
+/// Serializes `value` as a GraphQL literal. Without `braces` the outer delimiters are stripped,
+/// which turns an object into a list of arguments.
 pub fn to_string<T>(value: &T, braces: bool) -> Result<String>
 where
-    T: Serialize,
+    T: Serialize + ?Sized,
 {
-    let mut serializer = Serializer {
-        output: String::new(),
-        skip_quotes: false,
-    };
+    let mut serializer = Serializer::default();
     value.serialize(&mut serializer)?;
 
     if !braces {
@@ -90,42 +74,99 @@ where
     Ok(serializer.output)
 }
 
+impl Serializer {
+    fn escape(&mut self, v: &str) {
+        self.output.push('"');
+        for c in v.chars() {
+            match c {
+                '"' => self.output += "\\\"",
+                '\\' => self.output += "\\\\",
+                '\n' => self.output += "\\n",
+                '\r' => self.output += "\\r",
+                '\t' => self.output += "\\t",
+                '\u{8}' => self.output += "\\b",
+                '\u{c}' => self.output += "\\f",
+                c if c.is_control() => write!(self.output, "\\u{:04x}", c as u32).unwrap(),
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+
+    fn key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        match key.serialize(serde_json::value::Serializer) {
+            Ok(serde_json::Value::String(key)) => self.output += name(&key)?,
+            _ => return Err(Error::KeyMustBeString),
+        }
+        self.output.push(':');
+        Ok(())
+    }
+
+    fn compound(&mut self, open: &'static str, close: &'static str) -> Compound<'_> {
+        self.output += open;
+        Compound { ser: self, first: true, close }
+    }
+}
+
+pub struct Compound<'a> {
+    ser: &'a mut Serializer,
+    first: bool,
+    close: &'static str,
+}
+
+impl Compound<'_> {
+    fn separate(&mut self) {
+        if !self.first {
+            self.ser.output.push(',');
+        }
+        self.first = false;
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.separate();
+        value.serialize(&mut *self.ser)
+    }
+
+    // Fields that are null or empty objects are left out, so that unset optional
+    // columns and relations fall back to their database defaults.
+    fn field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        let mut temp = Serializer::default();
+        value.serialize(&mut temp)?;
+
+        if temp.output == "null" || temp.output == "{}" {
+            return Ok(());
+        }
+
+        self.separate();
+        self.ser.output += name(key)?;
+        self.ser.output.push(':');
+        self.ser.output += &temp.output;
+        Ok(())
+    }
+
+    fn close(self) -> Result<()> {
+        self.ser.output += self.close;
+        Ok(())
+    }
+}
+
 impl<'a> ser::Serializer for &'a mut Serializer {
-    // The output type produced by this `Serializer` during successful
-    // serialization. Most serializers that produce text or binary output should
-    // set `Ok = ()` and serialize into an `io::Write` or buffer contained
-    // within the `Serializer` instance, as happens here. Serializers that build
-    // in-memory data structures may be simplified by using `Ok` to propagate
-    // the data structure around.
     type Ok = ();
-
-    // The error type when some error occurs during serialization.
     type Error = Error;
 
-    // Associated types for keeping track of additional state while serializing
-    // compound data structures like sequences and maps. In this case no
-    // additional state is required beyond what is already stored in the
-    // Serializer struct.
-    type SerializeSeq = Self;
-    type SerializeTuple = Self;
-    type SerializeTupleStruct = Self;
-    type SerializeTupleVariant = Self;
-    type SerializeMap = Self;
-    type SerializeStruct = Self;
-    type SerializeStructVariant = Self;
-
-    // Here we go with the simple methods. The following 12 methods receive one
-    // of the primitive types of the data model and map it to JSON by appending
-    // into the output string.
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
     fn serialize_bool(self, v: bool) -> Result<()> {
         self.output += if v { "true" } else { "false" };
         Ok(())
     }
 
-    // JSON does not distinguish between different sizes of integers, so all
-    // signed integers will be serialized the same and all unsigned integers
-    // will be serialized the same. Other formats, especially compact binary
-    // formats, may need independent logic for the different sizes.
     fn serialize_i8(self, v: i8) -> Result<()> {
         self.serialize_i64(i64::from(v))
     }
@@ -138,13 +179,16 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         self.serialize_i64(i64::from(v))
     }
 
-    // Not particularly efficient but this is example code anyway. A more
-    // performant approach would be to use the `itoa` crate.
     fn serialize_i64(self, v: i64) -> Result<()> {
         self.output += &v.to_string();
         Ok(())
     }
 
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.output += &v.to_string();
+        Ok(())
+    }
+
     fn serialize_u8(self, v: u8) -> Result<()> {
         self.serialize_u64(u64::from(v))
     }
@@ -158,48 +202,43 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
-        // CHANGES MADE: quotes around integers
-        self.output += "\"";
         self.output += &v.to_string();
-        self.output += "\"";
         Ok(())
     }
 
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.output += &v.to_string();
+        Ok(())
+    }
+
+    // Widening to f64 would print the binary expansion, e.g. 0.10000000149011612 for 0.1.
     fn serialize_f32(self, v: f32) -> Result<()> {
-        self.serialize_f64(f64::from(v))
+        if !v.is_finite() {
+            return Err(Error::NonFiniteFloat(f64::from(v)));
+        }
+        self.output += &v.to_string();
+        Ok(())
     }
 
+    // Display for floats never uses an exponent, so its output is always a valid Int or Float literal.
     fn serialize_f64(self, v: f64) -> Result<()> {
-        // CHANGES MADE: no quotes around floats
-        self.output += "\"";
+        if !v.is_finite() {
+            return Err(Error::NonFiniteFloat(v));
+        }
         self.output += &v.to_string();
-        self.output += "\"";
         Ok(())
     }
 
-    // Serialize a char as a single-character string. Other formats may
-    // represent this differently.
     fn serialize_char(self, v: char) -> Result<()> {
-        self.serialize_str(&v.to_string())
+        self.escape(v.encode_utf8(&mut [0; 4]));
+        Ok(())
     }
 
-    // This only works for strings that don't require escape sequences but you
-    // get the idea. For example it would emit invalid JSON if the input string
-    // contains a '"' character.
     fn serialize_str(self, v: &str) -> Result<()> {
-        if !self.skip_quotes {
-            self.output += "\""
-        }
-        self.output += v;
-        if !self.skip_quotes {
-            self.output += "\""
-        }
+        self.escape(v);
         Ok(())
     }
 
-    // Serialize a byte array as an array of bytes. Could also use a base64
-    // string here. Binary formats will typically represent byte arrays more
-    // compactly.
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         use serde::ser::SerializeSeq;
         let mut seq = self.serialize_seq(Some(v.len()))?;
@@ -209,16 +248,10 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         seq.end()
     }
 
-    // An absent optional is represented as the JSON `null`.
     fn serialize_none(self) -> Result<()> {
         self.serialize_unit()
     }
 
-    // A present optional is represented as just the contained value. Note that
-    // this is a lossy representation. For example the values `Some(())` and
-    // `None` both serialize as just `null`. Unfortunately this is typically
-    // what people expect when working with JSON. Other formats are encouraged
-    // to behave more intelligently if possible.
     fn serialize_some<T>(self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
@@ -226,35 +259,29 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         value.serialize(self)
     }
 
-    // In Serde, unit means an anonymous value containing no data. Map this to
-    // JSON as `null`.
     fn serialize_unit(self) -> Result<()> {
         self.output += "null";
         Ok(())
     }
 
-    // Unit struct means a named value containing no data. Again, since there is
-    // no data, map this to JSON as `null`. There is no need to serialize the
-    // name in most formats.
     fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
         self.serialize_unit()
     }
 
-    // When serializing a unit variant (or any other kind of variant), formats
-    // can choose whether to keep track of it by index or by name. Binary
-    // formats typically use the index of the variant and human-readable formats
-    // typically use the name.
+    // Unit variants are GraphQL enum values and are written without quotes.
     fn serialize_unit_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
         variant: &'static str,
     ) -> Result<()> {
-        self.serialize_str(variant)
+        if matches!(variant, "true" | "false" | "null") {
+            return Err(Error::ReservedEnumValue(variant.to_string()));
+        }
+        self.output += name(variant)?;
+        Ok(())
     }
 
-    // As is done here, serializers are encouraged to treat newtype structs as
-    // insignificant wrappers around the data they contain.
     fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
@@ -262,11 +289,7 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         value.serialize(self)
     }
 
-    // Note that newtype variant (and all of the other variant serialization
-    // methods) refer exclusively to the "externally tagged" enum
-    // representation.
-    //
-    // Serialize this to JSON in externally tagged form as `{ NAME: VALUE }`.
+    // Other variants use the externally tagged representation `{variant: value}`.
     fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
@@ -277,38 +300,21 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        self.output += "{";
-        variant.serialize(&mut *self)?;
-        self.output += ":";
+        self.output.push('{');
+        self.key(variant)?;
         value.serialize(&mut *self)?;
-        self.output += "}";
+        self.output.push('}');
         Ok(())
     }
 
-    // Now we get to the serialization of compound types.
-    //
-    // The start of the sequence, each value, and the end are three separate
-    // method calls. This one is responsible only for serializing the start,
-    // which in JSON is `[`.
-    //
-    // The length of the sequence may or may not be known ahead of time. This
-    // doesn't make a difference in JSON because the length is not represented
-    // explicitly in the serialized form. Some serializers may only be able to
-    // support sequences for which the length is known up front.
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        self.output += "[";
-        Ok(self)
+        Ok(self.compound("[", "]"))
     }
 
-    // Tuples look just like sequences in JSON. Some formats may be able to
-    // represent tuples more efficiently by omitting the length, since tuple
-    // means that the corresponding `Deserialize implementation will know the
-    // length without needing to look at the serialized data.
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
         self.serialize_seq(Some(len))
     }
 
-    // Tuple structs look just like sequences in JSON.
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
@@ -317,8 +323,6 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         self.serialize_seq(Some(len))
     }
 
-    // Tuple variants are represented in JSON as `{ NAME: [DATA...] }`. Again
-    // this method is only responsible for the externally tagged representation.
     fn serialize_tuple_variant(
         self,
         _name: &'static str,
@@ -326,29 +330,19 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.output += "{";
-        variant.serialize(&mut *self)?;
-        self.output += ":[";
-        Ok(self)
+        self.output.push('{');
+        self.key(variant)?;
+        Ok(self.compound("[", "]}"))
     }
 
-    // Maps are represented in JSON as `{ K: V, K: V, ... }`.
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        self.output += "{";
-        Ok(self)
+        Ok(self.compound("{", "}"))
     }
 
-    // Structs look just like maps in JSON. In particular, JSON requires that we
-    // serialize the field names of the struct. Other formats may be able to
-    // omit the field names when serializing structs because the corresponding
-    // Deserialize implementation is required to know what the keys are without
-    // looking at the serialized data.
     fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
         self.serialize_map(Some(len))
     }
 
-    // Struct variants are represented in JSON as `{ NAME: { K: V, ... } }`.
-    // This is the externally tagged representation.
     fn serialize_struct_variant(
         self,
         _name: &'static str,
@@ -356,46 +350,29 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.output += "{";
-        variant.serialize(&mut *self)?;
-        self.output += ":{";
-        Ok(self)
+        self.output.push('{');
+        self.key(variant)?;
+        Ok(self.compound("{", "}}"))
     }
 }
 
-// The following 7 impls deal with the serialization of compound types like
-// sequences and maps. Serialization of such types is begun by a Serializer
-// method and followed by zero or more calls to serialize individual elements of
-// the compound type and one call to end the compound type.
-//
-// This impl is SerializeSeq so these methods are called after `serialize_seq`
-// is called on the Serializer.
-impl<'a> ser::SerializeSeq for &'a mut Serializer {
-    // Must match the `Ok` type of the serializer.
+impl ser::SerializeSeq for Compound<'_> {
     type Ok = ();
-    // Must match the `Error` type of the serializer.
     type Error = Error;
 
-    // Serialize a single element of the sequence.
     fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ",";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
-    // Close the sequence.
     fn end(self) -> Result<()> {
-        self.output += "]";
-        Ok(())
+        self.close()
     }
 }
 
-// Same thing but for tuples.
-impl<'a> ser::SerializeTuple for &'a mut Serializer {
+impl ser::SerializeTuple for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
@@ -403,20 +380,15 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ",";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "]";
-        Ok(())
+        self.close()
     }
 }
 
-// Same thing but for tuple structs.
-impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
+impl ser::SerializeTupleStruct for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
@@ -424,28 +396,15 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ",";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "]";
-        Ok(())
+        self.close()
     }
 }
 
-// Tuple variants are a little different. Refer back to the
-// `serialize_tuple_variant` method above:
-//
-//    self.output += "{";
-//    variant.serialize(&mut *self)?;
-//    self.output += ":[";
-//
-// So the `end` method in this impl is responsible for closing both the `]` and
-// the `}`.
-impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
+impl ser::SerializeTupleVariant for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
@@ -453,71 +412,39 @@ impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('[') {
-            self.output += ",";
-        }
-        value.serialize(&mut **self)
+        self.element(value)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "]}";
-        Ok(())
+        self.close()
     }
 }
 
-// Some `Serialize` types are not able to hold a key and value in memory at the
-// same time so `SerializeMap` implementations are required to support
-// `serialize_key` and `serialize_value` individually.
-//
-// There is a third optional method on the `SerializeMap` trait. The
-// `serialize_entry` method allows serializers to optimize for the case where
-// key and value are both available simultaneously. In JSON it doesn't make a
-// difference so the default behavior for `serialize_entry` is fine.
-impl<'a> ser::SerializeMap for &'a mut Serializer {
+impl ser::SerializeMap for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
-    // The Serde data model allows map keys to be any serializable type. JSON
-    // only allows string keys so the implementation below will produce invalid
-    // JSON if the key serializes as something other than a string.
-    //
-    // A real JSON serializer would need to validate that map keys are strings.
-    // This can be done by using a different Serializer to serialize the key
-    // (instead of `&mut **self`) and having that other serializer only
-    // implement `serialize_str` and return an error on any other data type.
     fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ",";
-        }
-        self.skip_quotes = true;
-        let res = key.serialize(&mut **self);
-        self.skip_quotes = false;
-        res
+        self.separate();
+        self.ser.key(key)
     }
 
-    // It doesn't make a difference whether the colon is printed at the end of
-    // `serialize_key` or at the beginning of `serialize_value`. In this case
-    // the code is a bit simpler having it here.
     fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        self.output += ":";
-        value.serialize(&mut **self)
+        value.serialize(&mut *self.ser)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "}";
-        Ok(())
+        self.close()
     }
 }
 
-// Structs are like maps in which the keys are constrained to be compile-time
-// constant strings.
-impl<'a> ser::SerializeStruct for &'a mut Serializer {
+impl ser::SerializeStruct for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
@@ -525,40 +452,15 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ",";
-        }
-
-        // Change: check whether it will serialize to null and skip it if so
-        let mut temp = Serializer {
-            output: String::new(),
-            skip_quotes: true,
-        };
-        value.serialize(&mut temp)?;
-
-        if temp.output == "null" {
-            return Ok(());
-        }
-        if temp.output == "{}" {
-            self.output.pop();
-            return Ok(());
-        }
-
-        // CHANGES MADE: no quotes around key
-        self.output += key;
-        self.output += ":";
-        value.serialize(&mut **self)
+        self.field(key, value)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "}";
-        Ok(())
+        self.close()
     }
 }
 
-// Similar to `SerializeTupleVariant`, here the `end` method is responsible for
-// closing both of the curly braces opened by `serialize_struct_variant`.
-impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
+impl ser::SerializeStructVariant for Compound<'_> {
     type Ok = ();
     type Error = Error;
 
@@ -566,17 +468,10 @@ impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('{') {
-            self.output += ",";
-        }
-
-        key.serialize(&mut **self)?;
-        self.output += ":";
-        value.serialize(&mut **self)
+        self.field(key, value)
     }
 
     fn end(self) -> Result<()> {
-        self.output += "}}";
-        Ok(())
+        self.close()
     }
 }
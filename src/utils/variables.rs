@@ -0,0 +1,135 @@
+use itertools::Itertools;
+use serde_json::{Map, Value};
+
+use crate::{Error, Flattened, Hasura, Render, Result};
+
+/// The values of an operation while its roots are rendered. `Context::inline()` writes them as
+/// GraphQL literals, `Context::variables()` as `$v1, $v2, ...` placeholders that are sent in
+/// the `variables` object of the request.
+///
+/// ```ignore
+/// let fetcher = Context::variables().query1(&articles);
+/// let fetcher = query!(Context::variables(); articles, managers);
+/// ```
+#[derive(Default)]
+pub struct Context {
+    variables: Option<Vec<(String, Value)>>,
+}
+
+impl Context {
+    pub fn inline() -> Self {
+        Self { variables: None }
+    }
+
+    pub fn variables() -> Self {
+        Self { variables: Some(vec![]) }
+    }
+
+    // Registers a value of the given GraphQL type, returning its placeholder, or None when inlining.
+    pub(crate) fn register(&mut self, ty: &str, value: impl FnOnce() -> Result<Value>) -> Result<Option<String>> {
+        let Some(variables) = self.variables.as_mut() else {
+            return Ok(None);
+        };
+        variables.push((ty.to_string(), value()?));
+        Ok(Some(format!("$v{}", variables.len())))
+    }
+
+    /// Wraps the rendered root fields into an operation, declaring all variables
+    /// registered while rendering them.
+    pub(crate) fn operation(self, operation: &str, roots: String) -> (String, Map<String, Value>) {
+        let variables = self.variables.unwrap_or_default();
+
+        let declarations = variables
+            .iter()
+            .enumerate()
+            .map(|(i, (ty, _))| format!("$v{}: {ty}", i + 1))
+            .join(", ");
+
+        let query = match declarations.is_empty() {
+            true => format!("{operation} {{ {roots} }}"),
+            false => format!("{operation}({declarations}) {{ {roots} }}"),
+        };
+
+        let values = variables
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, value))| (format!("v{}", i + 1), value))
+            .collect();
+
+        (query, values)
+    }
+}
+
+// Drops null and empty object fields, mirroring what the literal serializer does for structs.
+fn strip(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, strip(v)))
+                .filter(|(_, v)| !v.is_null() && v.as_object().is_none_or(|o| !o.is_empty()))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(strip).collect()),
+        value => value,
+    }
+}
+
+// Only rows go through `strip`; operands such as `_contains: {deleted: null}` must keep their nulls.
+pub(crate) fn to_row<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Result<Value> {
+    serde_json::to_value(value).map(strip)
+}
+
+// A serialization error caught while building an operation, reported once it gets rendered.
+pub(crate) fn deferred(error: &Option<String>) -> Result<()> {
+    match error {
+        Some(error) => Err(Error::Serde(serde::ser::Error::custom(error))),
+        None => Ok(()),
+    }
+}
+
+/// A value that renders inline, or as a placeholder of the given GraphQL type when the context collects variables.
+pub struct Variable<'a> {
+    ty: String,
+    inline: Box<dyn Render + 'a>,
+    value: Box<dyn Fn() -> Result<Value> + 'a>,
+}
+
+impl<'a> Variable<'a> {
+    pub fn new(ty: String, inline: impl Render + 'a, value: impl Fn() -> Result<Value> + 'a) -> Self {
+        Self {
+            ty,
+            inline: Box::new(inline),
+            value: Box::new(value),
+        }
+    }
+}
+
+impl<'a> Render for Variable<'a> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        match context.register(&self.ty, &self.value)? {
+            Some(placeholder) => Ok(placeholder),
+            None => self.inline.render(context),
+        }
+    }
+}
+
+/// Primary key arguments of a `_by_pk` field, one variable per column when the context collects variables.
+pub struct PkArgs<'a, T: Hasura>(pub &'a T::Pk);
+
+impl<'a, T: Hasura> Render for PkArgs<'a, T> {
+    fn render(&self, context: &mut Context) -> Result<String> {
+        if context.variables.is_none() {
+            return Flattened(self.0).render(context);
+        }
+
+        let value = serde_json::to_value(self.0)?;
+        let mut args = vec![];
+        for (column, ty) in T::pk_types() {
+            let Some(value) = value.get(column) else { continue };
+            if let Some(placeholder) = context.register(ty, || Ok(value.clone()))? {
+                args.push(format!("{column}: {placeholder}"));
+            }
+        }
+        Ok(args.join(", "))
+    }
+}
@@ -2,7 +2,7 @@ use hasura::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hasura)]
-#[hasura(table = "articles")]
+#[hasura(table = "articles", constraint = "articles_pkey", constraint = "articles_name_key")]
 pub struct Article {
     #[hasura(pk = "String")]
     name: String,
@@ -44,7 +44,7 @@ pub struct Store {
 fn simple_query() {
     let managers: Query<Manager> = Query::new();
 
-    assert_eq!(managers.to_string(), "managers { name weight }");
+    assert_eq!(managers.to_graphql().unwrap(), "managers { name weight }");
 }
 
 #[cfg(test)]
@@ -55,7 +55,7 @@ fn query_by_pk() {
     });
 
     assert_eq!(
-        manager.to_string(),
+        manager.to_graphql().unwrap(),
         "managers_by_pk(name:\"Boris\", ) { name weight }"
     );
 }
@@ -64,16 +64,238 @@ fn query_by_pk() {
 #[test]
 fn complex_query() {
     let managers: Query<Manager> = Query::new()
-        .distinct_on(Manager::name())
+        .distinct_on(ManagerColumn::Name)
         .offset(10u64)
         .limit(10u64);
 
     assert_eq!(
-        managers.to_string(),
+        managers.to_graphql().unwrap(),
         "managers(distinct_on: name, limit: 10, offset: 10) { name weight }"
     );
 }
 
+#[cfg(test)]
+#[test]
+fn order_by() {
+    let stores: Query<Store> = Query::new()
+        .order_by(vec![
+            OrderBy::relation(Store::manager, OrderBy::Asc(Manager::name())),
+            OrderBy::aggregate(Store::articles, AggregateOrderBy::Count(Direction::Desc)),
+            OrderBy::aggregate(Store::articles, AggregateOrderBy::Max(OrderBy::DescNullsLast(Article::price()))),
+            OrderBy::Asc(Store::id()),
+        ])
+        .returning(Fields { inner: vec![Store::id()] });
+
+    assert_eq!(
+        stores.to_graphql().unwrap(),
+        "stores(order_by: [{manager: {name: asc}}, {articles_aggregate: {count: desc}}, {articles_aggregate: {max: {price: desc_nulls_last}}}, {id: asc}]) { id }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn nested_arguments() {
+    let articles = Nested::new(Fields { inner: vec![Article::name(), Article::price()] })
+        .conditions(Conditions::single(Article::category(), Eq("fruits")))
+        .order_by(vec![OrderBy::Desc(Article::price())])
+        .limit(5);
+    let stores: Query<Store> = Query::new().returning(Fields { inner: vec![Store::id(), Store::articles(articles)] });
+
+    assert_eq!(
+        stores.to_graphql().unwrap(),
+        "stores { id articles(order_by: [{price: desc}], limit: 5, where: { category: { _eq: \"fruits\" } }) { name price } }"
+    );
+
+    let stores = stores.limit(2);
+    let fetcher = query!(Context::variables(); stores);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["query"],
+        "query($v1: articles_bool_exp!) { stores(limit: 2) { id articles(order_by: [{price: desc}], limit: 5, where: $v1) { name price } } }"
+    );
+    assert_eq!(body["variables"], serde_json::json!({"v1": {"category": {"_eq": "fruits"}}}));
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Hasura)]
+#[hasura(table = "stores")]
+pub struct StoreSummary {
+    #[hasura(pk = "u64")]
+    id: Option<u64>,
+
+    #[hasura(relation = "Article")]
+    #[serde(with = "relation")]
+    #[serde(default)]
+    articles: Vec<Article>,
+
+    #[hasura(aggregate_of = "articles")]
+    #[serde(skip_serializing)]
+    articles_aggregate: Aggregate<Article>,
+}
+
+#[cfg(test)]
+#[test]
+fn nested_aggregate() {
+    let summaries: Query<StoreSummary> = Query::new().returning(StoreSummary::except(&[StoreSummary::articles(Article::all())]));
+
+    assert_eq!(
+        summaries.to_graphql().unwrap(),
+        "stores { id articles_aggregate { aggregate { count } } }"
+    );
+
+    let aggregate = AggregateFields { max: vec![Article::price()], ..Default::default() };
+    let aggregate = NestedAggregate::new(aggregate)
+        .conditions(Conditions::single(Article::category(), Eq("fruits")))
+        .nodes(Article::all());
+    let summaries: Query<StoreSummary> = Query::new()
+        .returning(Fields { inner: vec![StoreSummary::id(), StoreSummary::articles_aggregate(aggregate)] });
+
+    assert_eq!(
+        summaries.to_graphql().unwrap(),
+        "stores { id articles_aggregate(where: { category: { _eq: \"fruits\" } }) { aggregate { count max { price } } nodes { name category price } } }"
+    );
+
+    let fetcher = query!(summaries);
+    let value = serde_json::json!({"stores": [{"id": 1, "articles_aggregate": {
+        "aggregate": {"count": 2, "max": {"price": 8}},
+        "nodes": [{"name": "apple", "category": "fruits", "price": 8}, {"name": "pear", "category": "fruits", "price": 5}]
+    }}]});
+    let stores = (fetcher.extract)(value).unwrap();
+
+    assert!(stores[0].articles.is_empty());
+    assert_eq!(stores[0].articles_aggregate.count(), Some(2));
+    assert_eq!(stores[0].articles_aggregate.max::<u64>(&Article::price()).unwrap(), Some(8));
+    assert_eq!(stores[0].articles_aggregate.nodes.len(), 2);
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Hasura)]
+#[hasura(table = "stores")]
+pub struct StoreStats {
+    #[hasura(pk = "u64")]
+    id: Option<u64>,
+
+    #[hasura(relation = "Article")]
+    #[serde(with = "relation")]
+    #[serde(default)]
+    articles: Vec<Article>,
+
+    #[hasura(aggregate_of = "articles")]
+    #[serde(skip_serializing)]
+    stats: Aggregate<Article>,
+}
+
+#[cfg(test)]
+#[test]
+fn nested_aggregate_alias() {
+    let stats: Query<StoreStats> = Query::new().returning(StoreStats::except(&[StoreStats::articles(Article::all())]));
+
+    assert_eq!(stats.to_graphql().unwrap(), "stores { id stats: articles_aggregate { aggregate { count } } }");
+
+    let fetcher = query!(stats);
+    let value = serde_json::json!({"stores": [{"id": 1, "stats": {"aggregate": {"count": 3}}}]});
+    let stores = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(stores[0].stats.count(), Some(3));
+}
+
+#[cfg(test)]
+#[test]
+fn aliases() {
+    let cheap: Query<Article> = Query::new()
+        .alias("cheap")
+        .conditions(Conditions::single(Article::price(), Lt(5u64)));
+    let expensive: Query<Article> = Query::new()
+        .alias("expensive")
+        .conditions(Conditions::single(Article::price(), Gte(5u64)));
+
+    assert_eq!(
+        cheap.to_graphql().unwrap(),
+        "cheap: articles(where: { price: { _lt: 5 } }) { name category price }"
+    );
+
+    let fetcher = query!(cheap, expensive);
+    let value = serde_json::json!({
+        "cheap": [{"name": "apple", "category": "fruits", "price": 2}],
+        "expensive": [{"name": "melon", "category": "fruits", "price": 9}]
+    });
+    let (cheap, expensive) = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(cheap[0].price, 2);
+    assert_eq!(expensive[0].price, 9);
+
+    // unaliased roots of the same table get aliased by position
+    let first: Query<Article> = Query::new().limit(1);
+    let second: Query<Article> = Query::new().limit(2);
+    let fetcher = query!(first, second);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&fetcher.body).unwrap()["query"],
+        "query { articles_0: articles(limit: 1) { name category price } articles_1: articles(limit: 2) { name category price } }"
+    );
+
+    let value = serde_json::json!({"articles_0": [], "articles_1": [{"name": "pear", "category": "fruits", "price": 3}]});
+    let (first, second) = (fetcher.extract)(value).unwrap();
+    assert!(first.is_empty());
+    assert_eq!(second[0].price, 3);
+
+    let insert = InsertOne::new(&expensive[0]).alias("melon").returning(Fields { inner: vec![Article::name()] });
+    let delete: DeleteByPk<Article> = DeleteByPk::new(Article::pk("apple", "fruits")).alias("apple");
+
+    assert_eq!(
+        mutation!(insert, delete).body,
+        serde_json::json!({
+            "query": "mutation { melon: insert_articles_one(object: {name:\"melon\",category:\"fruits\",price:9}) { name } apple: delete_articles_by_pk(name:\"apple\",category:\"fruits\", ) { name category price } }",
+            "variables": {}
+        }).to_string()
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn batching() {
+    let managers: Query<Manager> = Query::new();
+    let stores: Query<Store> = Query::new().returning(Fields { inner: vec![Store::id()] });
+    let articles: QueryAggregate<Article> = QueryAggregate::new();
+
+    let fetcher = query!(managers, stores, articles, QueryByPk::<Manager>::new(Manager::pk("John")));
+    let value = serde_json::json!({
+        "managers": [],
+        "stores": [{"id": 1, "manager_id": null, "articles": [], "manager": null}],
+        "articles_aggregate": {"aggregate": {"count": 4}},
+        "managers_by_pk": null
+    });
+    let (managers, stores, articles, john) = (fetcher.extract)(value).unwrap();
+
+    assert!(managers.is_empty());
+    assert_eq!(stores[0].id, Some(1));
+    assert_eq!(articles.count(), Some(4));
+    assert!(john.is_none());
+
+    let managers: Query<Manager> = Query::new();
+    let stores: Query<Store> = Query::new();
+    #[allow(deprecated)]
+    let fetcher = query2(&managers, &stores);
+    assert_eq!(fetcher.body, query!(managers, stores).body);
+
+    let names = ["apple", "pear"];
+    let batch: Vec<QueryByPk<Article>> = names.iter().map(|name| QueryByPk::new(Article::pk(*name, "fruits"))).collect();
+    let fetcher = query(&batch);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["query"],
+        "query { articles_by_pk_0: articles_by_pk(name:\"apple\",category:\"fruits\", ) { name category price } articles_by_pk_1: articles_by_pk(name:\"pear\",category:\"fruits\", ) { name category price } }"
+    );
+
+    let value = serde_json::json!({
+        "articles_by_pk_0": {"name": "apple", "category": "fruits", "price": 2},
+        "articles_by_pk_1": null
+    });
+    let found = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(found[0].as_ref().map(|article| article.price), Some(2));
+    assert!(found[1].is_none());
+}
+
 #[cfg(test)]
 #[test]
 fn simple_insert() {
@@ -85,8 +307,8 @@ fn simple_insert() {
     let insert = InsertOne::new(&manager);
 
     assert_eq!(
-        insert.to_string(),
-        "insert_managers_one(object: {name:\"John\",weight:\"10\"}) { name weight }"
+        insert.to_graphql().unwrap(),
+        "insert_managers_one(object: {name:\"John\",weight:10}) { name weight }"
     );
 }
 
@@ -120,7 +342,50 @@ fn complex_insert() {
     let stores = [store0, store1];
     let insert = Insert::new(&stores);
 
-    assert_eq!(insert.to_string(), "insert_stores(objects: [{articles:{data:[{name:\"1\",category:\"1\",price:\"1\"}]}}, {articles:{data:[{name:\"0\",category:\"0\",price:\"0\"}]}}]) { returning { id manager_id articles { name category price } manager { name weight } } }")
+    assert_eq!(insert.to_graphql().unwrap(), "insert_stores(objects: [{articles:{data:[{name:\"1\",category:\"1\",price:1}]}}, {articles:{data:[{name:\"0\",category:\"0\",price:0}]}}]) { returning { id manager_id articles { name category price } manager { name weight } } }")
+}
+
+#[cfg(test)]
+#[test]
+fn upsert() {
+    let articles = [Article {
+        name: "apple".to_string(),
+        category: "fruits".to_string(),
+        price: 7,
+    }];
+    let on_conflict = OnConflict::new(ArticleConstraint::ArticlesPkey)
+        .update_columns(vec![ArticleColumn::Price])
+        .conditions(Conditions::single(Article::price(), Lt(7u64)));
+    let insert = Insert::new(&articles).on_conflict(on_conflict).affected_rows(true);
+
+    assert_eq!(
+        insert.to_graphql().unwrap(),
+        "insert_articles(objects: [{name:\"apple\",category:\"fruits\",price:7}], on_conflict: {constraint: articles_pkey, update_columns: [price], where: { price: { _lt: 7 } }}) { returning { name category price } affected_rows }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn column_enums() {
+    assert_eq!(ArticleColumn::Category.to_string(), "category");
+    assert_eq!(ArticleConstraint::ArticlesNameKey.to_string(), "articles_name_key");
+    assert_eq!(serde_json::to_value(StoreColumn::ManagerId).unwrap(), "manager_id");
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn invalid_literals() {
+    let articles: Delete<Article> = Delete::new().conditions(Conditions::single(Article::price(), Eq(f64::NAN)));
+    assert!(matches!(articles.to_graphql(), Err(Error::Literal(LiteralError::NonFiniteFloat(_)))));
+
+    let error = mutation!(articles).send("http://localhost:8080/v1/graphql").await.err();
+    assert!(matches!(error, Some(Error::Literal(LiteralError::NonFiniteFloat(_)))));
+
+    let image = serde_json::json!({"content-type": "image/png"});
+    let quizzes: Query<Quiz> = Query::new().conditions(Conditions::single(Quiz::image(), Contains(image)));
+
+    let error = query!(quizzes).send("http://localhost:8080/v1/graphql").await.err();
+    assert!(matches!(error, Some(Error::Literal(LiteralError::InvalidName(name))) if name == "content-type"));
 }
 
 #[cfg(test)]
@@ -130,11 +395,75 @@ fn simple_delete() {
     let articles = Delete::new().conditions(conditions);
 
     assert_eq!(
-        articles.to_string(),
-        "delete_articles(where: { name: { _eq : \"apple\" } }) { returning { name category price } }"
+        articles.to_graphql().unwrap(),
+        "delete_articles(where: { name: { _eq: \"apple\" } }) { returning { name category price } }"
     )
 }
 
+#[cfg(test)]
+#[tokio::test]
+async fn unconditional_delete() {
+    let articles: Delete<Article> = Delete::new();
+    assert!(matches!(articles.to_graphql(), Err(Error::Unconditional(_))));
+
+    let error = mutation!(articles).send("http://localhost:8080/v1/graphql").await.err();
+    assert!(matches!(error, Some(Error::Unconditional(name)) if name == "delete_articles"));
+
+    let articles: Delete<Article> = Delete::new().all_rows();
+    assert_eq!(
+        articles.to_graphql().unwrap(),
+        "delete_articles(where: {  }) { returning { name category price } }"
+    );
+    let conditions = Conditions::single(Article::name(), Eq("apple"));
+    let unconditional = [
+        Conditions::None.and(Conditions::None),
+        Conditions::None.not(),
+        Conditions::None.or(conditions),
+        Conditions::many(Article::name(), vec![]),
+    ];
+    for conditions in unconditional {
+        let updates: UpdateMany<Article> = UpdateMany::new().update(conditions, Set::new().set(Article::price(), 1));
+        assert!(matches!(updates.to_graphql(), Err(Error::Unconditional(name)) if name == "update_articles_many"));
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn mutation_response() {
+    let conditions = Conditions::single(Article::category(), Eq("fruits"));
+    let articles = Delete::new()
+        .conditions(conditions)
+        .affected_rows(true)
+        .returning(Fields { inner: vec![] });
+
+    assert_eq!(
+        articles.to_graphql().unwrap(),
+        "delete_articles(where: { category: { _eq: \"fruits\" } }) { affected_rows }"
+    );
+
+    let fetcher = mutation!(articles);
+    let value = serde_json::json!({"delete_articles": {"affected_rows": 3}});
+    let response = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(response.affected_rows, Some(3));
+    assert!(response.returning.is_empty());
+
+    let article = Article {
+        name: "apple".to_string(),
+        category: "fruits".to_string(),
+        price: 2,
+    };
+    let objects = [article.clone()];
+    let insert = Insert::new(&objects).affected_rows(true);
+
+    let fetcher = mutation!(insert);
+    let value = serde_json::json!({"insert_articles": {"affected_rows": 1, "returning": [article]}});
+    let response = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(response.affected_rows, Some(1));
+    assert_eq!(response.returning[0].price, article.price);
+}
+
 #[cfg(test)]
 #[test]
 fn conditions() {
@@ -142,11 +471,257 @@ fn conditions() {
     let managers = Delete::new().conditions(conditions);
 
     assert_eq!(
-        managers.to_string(),
-        "delete_managers(where: { name: { _ilike : \"%J%\" } }) { returning { name weight } }"
+        managers.to_graphql().unwrap(),
+        "delete_managers(where: { name: { _ilike: \"%J%\" } }) { returning { name weight } }"
     )
 }
 
+#[cfg(test)]
+#[test]
+fn comparison_operators() {
+    let name: Vec<Box<dyn Condition>> = vec![Box::new(In(vec!["apple", "pear"])), Box::new(Nregex("^x"))];
+    let conditions = Conditions::many(Article::name(), name)
+        .and(Conditions::single(Article::price(), Lte(10u64)))
+        .and(Conditions::single(Article::category(), IsNull(false)));
+    let articles = Delete::new().conditions(conditions);
+
+    assert_eq!(
+        articles.to_graphql().unwrap(),
+        "delete_articles(where: { _and: [{ _and: [{ name: { _in: [\"apple\",\"pear\"], _nregex: \"^x\" } }, { price: { _lte: 10 } }] }, { category: { _is_null: false } }] }) { returning { name category price } }"
+    );
+    assert_eq!(
+        articles.conditions.value().unwrap(),
+        serde_json::json!({"_and": [
+            {"_and": [{"name": {"_in": ["apple", "pear"], "_nregex": "^x"}}, {"price": {"_lte": 10}}]},
+            {"category": {"_is_null": false}}
+        ]})
+    );
+}
+
+#[derive(Serialize)]
+pub struct EmployeeId(i32);
+
+impl ScalarValue for EmployeeId {}
+
+#[cfg(test)]
+#[test]
+fn scalar_conditions() {
+    let hired = chrono::NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+    let conditions = Conditions::single(Employee::id(), Gte(3i32))
+        .and(Conditions::single(Employee::id(), Nin(vec![EmployeeId(7)])))
+        .and(Conditions::single(Employee::nickname(), Neq(Some("boss"))))
+        .and(Conditions::single(Employee::hired(), Lt(hired)));
+    let employees = Delete::new().conditions(conditions);
+
+    assert_eq!(
+        employees.to_graphql().unwrap(),
+        "delete_employees(where: { _and: [{ _and: [{ _and: [{ id: { _gte: 3 } }, { id: { _nin: [7] } }] }, { nickname: { _neq: \"boss\" } }] }, { hired: { _lt: \"2023-04-01\" } }] }) { returning { id level rating nickname hired } }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn jsonb_conditions() {
+    let image = Image {
+        url: "https://example.com/a.png".to_string(),
+        blurhash: None,
+    };
+    let cast: Vec<Box<dyn Condition>> = vec![Box::new(Ilike("%png%"))];
+    let conditions = Conditions::single(Quiz::image(), Contains(image))
+        .or(Conditions::single(Quiz::image(), HasKeysAll(vec!["url", "blurhash"])))
+        .or(Conditions::single(Quiz::image(), Cast(cast)));
+    let quizzes: Query<Quiz> = Query::new()
+        .conditions(conditions)
+        .returning(Fields { inner: vec![Quiz::id()] });
+
+    assert_eq!(
+        quizzes.to_graphql().unwrap(),
+        "quizzes(where: { _or: [{ _or: [{ image: { _contains: {url:\"https://example.com/a.png\"} } }, { image: { _has_keys_all: [\"url\",\"blurhash\"] } }] }, { image: { _cast: { String: { _ilike: \"%png%\" } } } }] }) { id }"
+    );
+
+    let conditions = quizzes.conditions.as_ref().unwrap();
+    assert_eq!(
+        conditions.value().unwrap()["_or"][1],
+        serde_json::json!({"image": {"_cast": {"String": {"_ilike": "%png%"}}}})
+    );
+}
+
+#[cfg(feature = "postgis")]
+#[derive(Serialize, Deserialize, Debug, Clone, Hasura)]
+#[hasura(table = "landmarks")]
+pub struct Landmark {
+    #[hasura(pk = "u64")]
+    id: Option<u64>,
+    location: Geometry,
+}
+
+#[cfg(all(test, feature = "postgis"))]
+#[test]
+fn postgis_conditions() {
+    let area = Geometry::Polygon {
+        coordinates: vec![vec![vec![0.0, 0.0], vec![0.0, 1.5], vec![1.5, 1.5], vec![0.0, 0.0]]],
+    };
+    let near = DWithin::new(1000.0, Geometry::point(4.35, 50.85)).use_spheroid(false);
+    let conditions = Conditions::single(Landmark::location(), StWithin(area))
+        .and(Conditions::single(Landmark::location(), StDWithin(near)));
+    let landmarks: Query<Landmark> = Query::new().conditions(conditions);
+
+    assert_eq!(
+        landmarks.to_graphql().unwrap(),
+        "landmarks(where: { _and: [{ location: { _st_within: {type:\"Polygon\",coordinates:[[[0,0],[0,1.5],[1.5,1.5],[0,0]]]} } }, { location: { _st_d_within: {distance:1000,from:{type:\"Point\",coordinates:[4.35,50.85]},use_spheroid:false} } }] }) { id location }"
+    );
+
+    let location: Geometry = serde_json::from_value(serde_json::json!({"type": "Point", "coordinates": [1.0, 2.0]})).unwrap();
+    assert_eq!(location, Geometry::point(1.0, 2.0));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hasura)]
+#[hasura(table = "categories")]
+pub struct Category {
+    #[hasura(pk = "String")]
+    path: String,
+    aliases: Vec<String>,
+}
+
+#[cfg(test)]
+#[test]
+fn array_conditions() {
+    let conditions = Conditions::single(Category::path(), Descendant("fruits"))
+        .and(Conditions::single(Category::path(), MatchesAny(vec!["*.apple", "*.pear"])))
+        .and(Conditions::single(Category::aliases(), Contains(vec!["pome"])));
+    let categories: Query<Category> = Query::new().conditions(conditions);
+
+    assert_eq!(
+        categories.to_graphql().unwrap(),
+        "categories(where: { _and: [{ _and: [{ path: { _descendant: \"fruits\" } }, { path: { _matches_any: [\"*.apple\",\"*.pear\"] } }] }, { aliases: { _contains: [\"pome\"] } }] }) { path aliases }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn column_conditions() {
+    let conditions = Conditions::single(Article::name(), Cne(Article::category()));
+    let articles = Delete::new().conditions(conditions);
+
+    assert_eq!(
+        articles.to_graphql().unwrap(),
+        "delete_articles(where: { name: { _cne: [\"category\"] } }) { returning { name category price } }"
+    );
+    assert_eq!(
+        articles.conditions.value().unwrap(),
+        serde_json::json!({"name": {"_cne": ["category"]}})
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn relation_conditions() {
+    let expensive = Conditions::relation(Store::articles, Conditions::single(Article::price(), Gt(5u64)));
+    let managed = Conditions::relation(Store::manager, Conditions::single(Manager::name(), Eq("John")));
+    let stores: Query<Store> = Query::new()
+        .conditions(expensive.and(managed.not()))
+        .returning(Fields { inner: vec![Store::id()] });
+
+    assert_eq!(
+        stores.to_graphql().unwrap(),
+        "stores(where: { _and: [{ articles: { price: { _gt: 5 } } }, { _not: { manager: { name: { _eq: \"John\" } } } }] }) { id }"
+    );
+
+    let fetcher = query!(Context::variables(); stores);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["variables"]["v1"],
+        serde_json::json!({"_and": [{"articles": {"price": {"_gt": 5}}}, {"_not": {"manager": {"name": {"_eq": "John"}}}}]})
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn aggregate_conditions() {
+    let count = Count::new(Gt(3u64))
+        .filter(Conditions::single(Article::category(), Eq("fruits")))
+        .distinct(true)
+        .arguments(vec![ArticleColumn::Name]);
+    let stores: Query<Store> = Query::new()
+        .conditions(Conditions::aggregate(Store::articles, count))
+        .returning(Fields { inner: vec![Store::id()] });
+
+    assert_eq!(
+        stores.to_graphql().unwrap(),
+        "stores(where: { articles_aggregate: { count: { predicate: { _gt: 3 }, filter: { category: { _eq: \"fruits\" } }, distinct: true, arguments: [name] } } }) { id }"
+    );
+
+    let fetcher = query!(Context::variables(); stores);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["variables"]["v1"],
+        serde_json::json!({"articles_aggregate": {"count": {
+            "predicate": {"_gt": 3},
+            "filter": {"category": {"_eq": "fruits"}},
+            "distinct": true,
+            "arguments": ["name"]
+        }}})
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn escaped_literals() {
+    let conditions = Conditions::single(Article::name(), Eq("a \"quoted\" \\ name\n"));
+    let articles = Delete::new().conditions(conditions);
+
+    assert_eq!(
+        articles.to_graphql().unwrap(),
+        r#"delete_articles(where: { name: { _eq: "a \"quoted\" \\ name\n" } }) { returning { name category price } }"#
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Junior,
+    Senior,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hasura)]
+#[hasura(table = "employees")]
+pub struct Employee {
+    #[hasura(pk = "i32")]
+    id: Option<i32>,
+    level: Level,
+    rating: f32,
+    nickname: Option<String>,
+    hired: Option<chrono::NaiveDate>,
+}
+
+#[cfg(test)]
+#[test]
+fn literal_values() {
+    let employee = Employee {
+        id: None,
+        level: Level::Senior,
+        rating: -0.5,
+        nickname: Some("\t\u{1}".to_string()),
+        hired: None,
+    };
+    let insert = InsertOne::new(&employee);
+
+    assert_eq!(
+        insert.to_graphql().unwrap(),
+        r#"insert_employees_one(object: {level:senior,rating:-0.5,nickname:"\t\u0001"}) { id level rating nickname hired }"#
+    );
+
+    let employees: Query<Employee> = Query::new()
+        .conditions(Conditions::single(Employee::rating(), Eq(0.1f32)))
+        .returning(Fields { inner: vec![Employee::id()] });
+    assert_eq!(employees.to_graphql().unwrap(), "employees(where: { rating: { _eq: 0.1 } }) { id }");
+
+    let employees: Query<Employee> = Query::new().conditions(Conditions::single(Employee::rating(), Eq(f32::INFINITY)));
+    assert!(matches!(employees.to_graphql(), Err(Error::Literal(LiteralError::NonFiniteFloat(_)))));
+}
+
 #[cfg(test)]
 #[test]
 fn update_by_pk() {
@@ -164,8 +739,138 @@ fn update_by_pk() {
     let updated = UpdateByPk::new(pk, &article);
 
     assert_eq!(
-        updated.to_string(),
-        "update_articles_by_pk(pk_columns: {name:\"apple\",category:\"fruits\"}, _set: {name:\"apple\",category:\"fruits\",price:\"7\"}) { name category price }"
+        updated.to_graphql().unwrap(),
+        "update_articles_by_pk(pk_columns: {name:\"apple\",category:\"fruits\"}, _set: {name:\"apple\",category:\"fruits\",price:7}) { name category price }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn partial_update() {
+    let updated = UpdateByPk::new(Article::pk("apple", "fruits"), Set::new().set(Article::price(), 9))
+        .returning(Fields { inner: vec![Article::price()] });
+
+    assert_eq!(
+        updated.to_graphql().unwrap(),
+        "update_articles_by_pk(pk_columns: {name:\"apple\",category:\"fruits\"}, _set: {price: 9}) { price }"
+    );
+
+    let updated = Update::new(Set::new().null(Store::manager_id()))
+        .conditions(Conditions::single(Store::id(), Eq(1u64)))
+        .returning(Fields { inner: vec![Store::id()] });
+
+    assert_eq!(
+        updated.to_graphql().unwrap(),
+        "update_stores(_set: {manager_id: null}, where: { id: { _eq: 1 } }) { returning { id } }"
+    );
+
+    let fetcher = mutation!(Context::variables(); updated);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+    assert_eq!(body["variables"]["v1"], serde_json::json!({"manager_id": null}));
+}
+
+#[cfg(test)]
+#[test]
+fn update_operators() {
+    let updated = Update::default()
+        .conditions(Conditions::single(Article::name(), Eq("apple".to_string())))
+        .inc(Article::price(), 2)
+        .returning(Fields { inner: vec![Article::price()] });
+
+    assert_eq!(
+        updated.to_graphql().unwrap(),
+        "update_articles(_inc: {price: 2}, where: { name: { _eq: \"apple\" } }) { returning { price } }"
+    );
+
+    let updated = UpdateByPk::new(Quiz::pk(3u64), Set::new())
+        .append(Quiz::image(), serde_json::json!({"blurhash": "LEHV6n"}))
+        .delete_key(Quiz::image(), "url")
+        .delete_at_path(Quiz::image(), vec!["meta", "size"])
+        .returning(Fields { inner: vec![Quiz::id()] });
+
+    let string = updated.to_graphql().unwrap();
+    assert!(!string.contains("_set"));
+    assert!(string.contains("_append: {image: {blurhash:\"LEHV6n\"}}"));
+    assert!(string.contains("_delete_key: {image: \"url\"}"));
+    assert!(string.contains("_delete_at_path: {image: [\"meta\",\"size\"]}"));
+
+    let fetcher = mutation!(Context::variables(); updated);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(body["variables"]["v2"], serde_json::json!({"image": {"blurhash": "LEHV6n"}}));
+    assert!(body["query"].as_str().unwrap().contains("$v2: quizzes_append_input!"));
+
+    // maps with non-string keys can't be turned into json
+    let image = std::collections::BTreeMap::from([((1, 2), "url")]);
+    let updated = UpdateByPk::new(Quiz::pk(3u64), Set::new()).append(Quiz::image(), image);
+    assert!(matches!(updated.to_graphql(), Err(Error::Serde(_))));
+}
+
+#[cfg(test)]
+#[test]
+fn update_many() {
+    let apple = Article {
+        name: "apple".to_string(),
+        category: "fruits".to_string(),
+        price: 8,
+    };
+    let pear = Article {
+        name: "pear".to_string(),
+        category: "fruits".to_string(),
+        price: 5,
+    };
+    let updates = UpdateMany::new()
+        .update(Conditions::single(Article::name(), Eq("apple")), &apple)
+        .update(Conditions::single(Article::name(), Eq("pear")), &pear)
+        .returning(Fields { inner: vec![Article::price()] });
+
+    assert_eq!(
+        updates.to_graphql().unwrap(),
+        "update_articles_many(updates: [{where: { name: { _eq: \"apple\" } }, _set: {name:\"apple\",category:\"fruits\",price:8}}, {where: { name: { _eq: \"pear\" } }, _set: {name:\"pear\",category:\"fruits\",price:5}}]) { returning { price } affected_rows }"
+    );
+
+    let fetcher = mutation!(updates);
+    let value = serde_json::json!({"update_articles_many": [
+        {"affected_rows": 1, "returning": [{"name": "apple", "category": "fruits", "price": 8}]},
+        {"affected_rows": 0, "returning": []}
+    ]});
+    let responses = (fetcher.extract)(value).unwrap();
+
+    assert_eq!(responses[0].affected_rows, Some(1));
+    assert_eq!(responses[0].returning[0].price, 8);
+    assert!(responses[1].returning.is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn update_many_operators() {
+    let updates: UpdateMany<Article> = UpdateMany::new()
+        .update_with(
+            Conditions::single(Article::name(), Eq("apple")),
+            Set::new(),
+            Operators::new().inc(Article::price(), 2),
+        )
+        .update_with(
+            Conditions::single(Article::name(), Eq("pear")),
+            Set::new().set(Article::category(), "fruits"),
+            Operators::new().inc(Article::price(), -1),
+        )
+        .returning(Fields { inner: vec![Article::price()] });
+
+    assert_eq!(
+        updates.to_graphql().unwrap(),
+        "update_articles_many(updates: [{where: { name: { _eq: \"apple\" } }, _inc: {price: 2}}, {where: { name: { _eq: \"pear\" } }, _set: {category: \"fruits\"}, _inc: {price: -1}}]) { returning { price } affected_rows }"
+    );
+
+    let fetcher = mutation!(Context::variables(); updates);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["variables"]["v1"],
+        serde_json::json!([
+            {"where": {"name": {"_eq": "apple"}}, "_inc": {"price": 2}},
+            {"where": {"name": {"_eq": "pear"}}, "_set": {"category": "fruits"}, "_inc": {"price": -1}}
+        ])
     );
 }
 
@@ -176,15 +881,201 @@ fn recursive_except() {
     let query = Query::new().returning(returning);
 
     assert_eq!(
-        query.to_string(),
+        query.to_graphql().unwrap(),
         "stores { id manager_id manager { name weight } }"
     );
 }
 
+#[cfg(test)]
+#[test]
+fn aggregate_query() {
+    let conditions = Conditions::single(Article::price(), Gt(5u64));
+    let aggregate: QueryAggregate<Article> = QueryAggregate::new()
+        .conditions(conditions)
+        .limit(10)
+        .sum(vec![Article::price()])
+        .max(vec![Article::price()])
+        .nodes(Article::all());
+
+    assert_eq!(
+        aggregate.to_graphql().unwrap(),
+        "articles_aggregate(limit: 10, where: { price: { _gt: 5 } }) { aggregate { count sum { price } max { price } } nodes { name category price } }"
+    );
+
+    let nodes: QueryAggregate<Article> = QueryAggregate::new().count(false).nodes(Article::all());
+    assert_eq!(nodes.to_graphql().unwrap(), "articles_aggregate { nodes { name category price } }");
+
+    let empty: QueryAggregate<Article> = QueryAggregate::new().count(false);
+    assert_eq!(empty.to_graphql().unwrap(), "articles_aggregate { aggregate { count } }");
+}
+
+#[cfg(test)]
+#[test]
+fn aggregate_decode() {
+    let value = serde_json::json!({
+        "aggregate": { "count": 2, "sum": { "price": 12 }, "max": { "price": null } },
+        "nodes": [{ "name": "apple", "category": "fruits", "price": 7 }]
+    });
+    let aggregate: Aggregate<Article> = serde_json::from_value(value).unwrap();
+
+    assert_eq!(aggregate.count(), Some(2));
+    assert_eq!(aggregate.sum::<u64>(&Article::price()).unwrap(), Some(12));
+    assert_eq!(aggregate.max::<u64>(&Article::price()).unwrap(), None);
+    assert_eq!(aggregate.min::<u64>(&Article::price()).unwrap(), None);
+    assert!(matches!(aggregate.sum::<String>(&Article::price()), Err(Error::Serde(_))));
+    assert_eq!(aggregate.nodes.len(), 1);
+}
+
+#[cfg(all(test, feature = "subscriptions"))]
+#[test]
+fn stream() {
+    let conditions = Conditions::single(Article::category(), Eq("fruits"));
+    let stream: Stream<Article> = Stream::new(100, Article::name(), "apple")
+        .ordering(CursorOrdering::Desc)
+        .conditions(conditions);
+
+    assert_eq!(
+        stream.to_graphql().unwrap(),
+        "articles_stream(batch_size: 100, cursor: {initial_value: {name:\"apple\"}, ordering: DESC}, where: { category: { _eq: \"fruits\" } }) { name category price }"
+    );
+
+    let subscriber = subscription!(Context::variables(); stream);
+
+    assert_eq!(
+        subscriber.query,
+        "subscription($v1: articles_stream_cursor_value_input!, $v2: articles_bool_exp!) { articles_stream(batch_size: 100, cursor: {initial_value: $v1, ordering: DESC}, where: $v2) { name category price } }"
+    );
+    assert_eq!(subscriber.variables["v1"], serde_json::json!({"name": "apple"}));
+
+    let stream: Stream<Article> = Stream::new(100, Article::name(), std::collections::BTreeMap::from([((1, 2), "apple")]));
+    assert!(matches!(stream.to_graphql(), Err(Error::Serde(_))));
+}
+
+#[cfg(test)]
+#[test]
+fn variables_query() {
+    let conditions = Conditions::single(Article::name(), Eq("say \"hi\""));
+    let articles: Query<Article> = Query::new().conditions(conditions).limit(5);
+
+    let fetcher = query!(Context::variables(); articles);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["query"],
+        "query($v1: articles_bool_exp!) { articles(limit: 5, where: $v1) { name category price } }"
+    );
+    assert_eq!(body["variables"], serde_json::json!({"v1": {"name": {"_eq": "say \"hi\""}}}));
+}
+
+#[cfg(test)]
+#[test]
+fn variables_keep_nulls() {
+    let image = serde_json::json!({"deleted": null});
+    let quizzes: Query<Quiz> = Query::new()
+        .conditions(Conditions::single(Quiz::image(), Contains(image)))
+        .returning(Fields { inner: vec![Quiz::id()] });
+
+    assert_eq!(quizzes.to_graphql().unwrap(), "quizzes(where: { image: { _contains: {deleted:null} } }) { id }");
+
+    let fetcher = query!(Context::variables(); quizzes);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+    assert_eq!(body["variables"]["v1"], serde_json::json!({"image": {"_contains": {"deleted": null}}}));
+}
+
+#[cfg(test)]
+#[test]
+fn variables_mutation() {
+    let store = Store {
+        id: None,
+        manager_id: Some("John".to_string()),
+        articles: vec![],
+        manager: None,
+    };
+    let insert = InsertOne::new(&store).returning(Store::except(&[Store::articles(Article::all())]));
+    let delete: DeleteByPk<Article> = DeleteByPk::new(Article::pk("apple", "fruits"));
+
+    let fetcher = mutation!(Context::variables(); insert, delete);
+    let body: serde_json::Value = serde_json::from_str(&fetcher.body).unwrap();
+
+    assert_eq!(
+        body["query"],
+        "mutation($v1: stores_insert_input!, $v2: String!, $v3: String!) { insert_stores_one(object: $v1) { id manager_id manager { name weight } } delete_articles_by_pk(name: $v2, category: $v3, ) { name category price } }"
+    );
+    assert_eq!(
+        body["variables"],
+        serde_json::json!({"v1": {"manager_id": "John", "articles": {"data": []}}, "v2": "apple", "v3": "fruits"})
+    );
+}
+
+#[cfg(all(test, feature = "subscriptions"))]
+#[tokio::test]
+async fn subscription_local() {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::WebSocketStream;
+
+    async fn receive(ws: &mut WebSocketStream<tokio::net::TcpStream>) -> serde_json::Value {
+        let message = ws.next().await.unwrap().unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    async fn send(ws: &mut WebSocketStream<tokio::net::TcpStream>, frames: &[&str]) {
+        for frame in frames {
+            ws.send(Message::text(*frame)).await.unwrap();
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1/graphql", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        #[allow(clippy::result_large_err)]
+        let callback = |request: &Request, mut response: Response| {
+            let protocol = request.headers()["sec-websocket-protocol"].clone();
+            assert_eq!(protocol, "graphql-transport-ws");
+            response.headers_mut().insert("sec-websocket-protocol", protocol);
+            Ok(response)
+        };
+        let mut ws = tokio_tungstenite::accept_hdr_async(stream, callback).await.unwrap();
+
+        let init = receive(&mut ws).await;
+        assert_eq!(init["type"], "connection_init");
+        assert_eq!(init["payload"]["headers"]["x-hasura-admin-secret"], "secret");
+        send(&mut ws, &[r#"{"type":"connection_ack"}"#, r#"{"type":"ping"}"#]).await;
+
+        let subscribe = receive(&mut ws).await;
+        assert_eq!(subscribe["type"], "subscribe");
+        assert_eq!(subscribe["payload"]["query"], "subscription { managers { name weight } }");
+        assert_eq!(receive(&mut ws).await["type"], "pong");
+
+        send(&mut ws, &[
+            r#"{"id":"1","type":"next","payload":{"data":{"managers":[]}}}"#,
+            r#"{"id":"1","type":"next","payload":{"data":{"managers":[{"name":"John","weight":10.0}]}}}"#,
+            r#"{"id":"1","type":"complete"}"#,
+        ]).await;
+    });
+
+    let managers: Query<Manager> = Query::new();
+    let stream = subscription!(managers)
+        .admin("secret".to_string())
+        .subscribe(&url)
+        .await
+        .unwrap();
+    let frames: Vec<Vec<Manager>> = stream.map(Result::unwrap).collect().await;
+
+    assert_eq!(frames.len(), 2);
+    assert!(frames[0].is_empty());
+    assert_eq!(frames[1][0].name, "John");
+
+    server.await.unwrap();
+}
+
 //////////////////////////////////////
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hasura)]
-#[hasura(table = "tags")]
+#[hasura(table = "tags", constraint = "tags_pkey")]
 pub struct Tag {
     #[hasura(pk = "u64")]
     pub quiz_id: Option<u64>,
@@ -232,5 +1123,5 @@ async fn simple_real() {
 
     let string = query!(body).admin(admin).send(&url).await.unwrap();
     println!("{string}");
-    let quiz = string.parse().unwrap();
+    let _quiz = string.parse().unwrap();
 }